@@ -0,0 +1,142 @@
+//! RPC interface for the Metasave pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_metasave_runtime_api::{ChangeRecord, Entry, Route, WorldTime};
+pub use pallet_metasave_runtime_api::MetasaveApi as MetasaveRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+#[rpc]
+pub trait MetasaveApi<BlockHash, GameID, AccountId, BlockNumber, Moment> {
+	/// A world entry with its revision, last writer and last-modified block and time.
+	#[rpc(name = "metasave_worldEntry")]
+	fn world_entry(
+		&self,
+		game: GameID,
+		route: Route,
+		key: Vec<u8>,
+		at: Option<BlockHash>,
+	) -> Result<Option<Entry<Vec<u8>, AccountId, BlockNumber, Moment>>>;
+
+	/// A user entry with its revision, last writer and last-modified block and time.
+	#[rpc(name = "metasave_userEntry")]
+	fn user_entry(
+		&self,
+		game: GameID,
+		user: AccountId,
+		route: Route,
+		key: Vec<u8>,
+		at: Option<BlockHash>,
+	) -> Result<Option<Entry<Vec<u8>, AccountId, BlockNumber, Moment>>>;
+
+	/// Every change of `game` after the change numbered `seq`, oldest first.
+	///
+	/// Clients pass the sequence number of the last change they saw, or 0. If the first change
+	/// returned is not `seq + 1`, older changes have left the log and the client must resync in
+	/// full.
+	#[rpc(name = "metasave_changesSince")]
+	fn changes_since(
+		&self,
+		game: GameID,
+		seq: u64,
+		at: Option<BlockHash>,
+	) -> Result<Vec<ChangeRecord<Vec<u8>, AccountId, BlockNumber>>>;
+
+	/// The current in-world time and phase of `game`, or `None` if it has no clock.
+	#[rpc(name = "metasave_worldTime")]
+	fn world_time(&self, game: GameID, at: Option<BlockHash>) -> Result<Option<WorldTime<Vec<u8>>>>;
+}
+
+/// Serves the Metasave RPC methods from the runtime API.
+pub struct Metasave<C, B> {
+	client: Arc<C>,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> Metasave<C, B> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error code of a failed runtime API call.
+const RUNTIME_ERROR: i64 = 1;
+
+/// Map a failed runtime API call to an RPC error.
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, GameID, AccountId, BlockNumber, Moment>
+	MetasaveApi<<Block as BlockT>::Hash, GameID, AccountId, BlockNumber, Moment> for Metasave<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: MetasaveRuntimeApi<Block, GameID, AccountId, BlockNumber, Moment>,
+	GameID: Codec,
+	AccountId: Codec,
+	BlockNumber: Codec,
+	Moment: Codec,
+{
+	fn world_entry(
+		&self,
+		game: GameID,
+		route: Route,
+		key: Vec<u8>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Entry<Vec<u8>, AccountId, BlockNumber, Moment>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.world_entry(&at, game, route, key)
+			.map_err(|e| runtime_error("Unable to query the world entry.", e))
+	}
+
+	fn user_entry(
+		&self,
+		game: GameID,
+		user: AccountId,
+		route: Route,
+		key: Vec<u8>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Entry<Vec<u8>, AccountId, BlockNumber, Moment>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.user_entry(&at, game, user, route, key)
+			.map_err(|e| runtime_error("Unable to query the user entry.", e))
+	}
+
+	fn changes_since(
+		&self,
+		game: GameID,
+		seq: u64,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<ChangeRecord<Vec<u8>, AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.changes_since(&at, game, seq)
+			.map_err(|e| runtime_error("Unable to query the change log.", e))
+	}
+
+	fn world_time(
+		&self,
+		game: GameID,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<WorldTime<Vec<u8>>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.world_time(&at, game).map_err(|e| runtime_error("Unable to read the world clock.", e))
+	}
+}
@@ -0,0 +1,46 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Runtime API for reading Metasave save data without submitting an extrinsic.
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+pub use pallet_metasave::{clock::WorldTime, numeric::Number, ChangeOp, ChangeRecord, Entry, HistoryEntry, Route};
+
+sp_api::decl_runtime_apis! {
+	pub trait MetasaveApi<GameID, AccountId, BlockNumber, Moment> where
+		GameID: Codec,
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Moment: Codec,
+	{
+		/// A world entry with its revision, last writer and last-modified block and time.
+		fn world_entry(game: GameID, route: Route, key: Vec<u8>) -> Option<Entry<Vec<u8>, AccountId, BlockNumber, Moment>>;
+
+		/// A user entry with its revision, last writer and last-modified block and time.
+		fn user_entry(game: GameID, user: AccountId, route: Route, key: Vec<u8>) -> Option<Entry<Vec<u8>, AccountId, BlockNumber, Moment>>;
+
+		/// Whether `bit` is set in a world entry, or `None` if there is no such entry.
+		fn world_test_bit(game: GameID, route: Route, key: Vec<u8>, bit: u32) -> Option<bool>;
+
+		/// Whether `bit` is set in a user entry, or `None` if there is no such entry.
+		fn user_test_bit(game: GameID, user: AccountId, route: Route, key: Vec<u8>, bit: u32) -> Option<bool>;
+
+		/// The recorded values of a world entry, oldest first. Empty unless the game keeps history.
+		fn world_history(game: GameID, route: Route, key: Vec<u8>) -> Vec<HistoryEntry<Vec<u8>, AccountId, BlockNumber>>;
+
+		/// The recorded values of a user entry, oldest first.
+		fn user_history(game: GameID, user: AccountId, route: Route, key: Vec<u8>) -> Vec<HistoryEntry<Vec<u8>, AccountId, BlockNumber>>;
+
+		/// Every change of `game` after the change numbered `seq`, oldest first. Clients that
+		/// get a first change other than `seq + 1` missed some and must resync in full.
+		fn changes_since(game: GameID, seq: u64) -> Vec<ChangeRecord<Vec<u8>, AccountId, BlockNumber>>;
+
+		/// The current in-world time and phase of `game`, or `None` if it has no clock.
+		fn world_time(game: GameID) -> Option<WorldTime<Vec<u8>>>;
+
+		/// The players ranked by a user key of `game` and their scores, best first, or `None` if
+		/// the key has no leaderboard.
+		fn leaderboard(game: GameID, route: Route, key: Vec<u8>) -> Option<Vec<(AccountId, Number)>>;
+	}
+}
@@ -0,0 +1,77 @@
+//! Treating entry values as bitfields, e.g. a save's unlocked achievements.
+//!
+//! Bits are numbered from the least significant bit of the first byte: bit 0 is `value[0] & 0x01`,
+//! bit 8 is `value[1] & 0x01`. A value of any length is a valid bitfield, and bits past its end
+//! are clear.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// An update applied to a bitfield entry.
+#[derive(Encode, Decode, RuntimeDebug, Clone, TypeInfo, PartialEq, Eq)]
+pub enum BitOp {
+	/// Set a single bit.
+	Set(u32),
+	/// Clear a single bit.
+	Clear(u32),
+	/// Flip a single bit.
+	Toggle(u32),
+	/// Set every bit that is set in the mask, leaving the others as they are.
+	SetMask(Vec<u8>),
+}
+
+impl BitOp {
+	/// Length the value must at least have once the operation is applied.
+	pub fn required_len(&self) -> usize
+	{
+		match self {
+			BitOp::Set(bit) | BitOp::Toggle(bit) => *bit as usize / 8 + 1,
+			BitOp::Clear(_) => 0,
+			BitOp::SetMask(mask) => mask.len(),
+		}
+	}
+
+	/// Apply the operation to `value`, extending it with zeros to `required_len()` first.
+	pub fn apply(&self, value : &mut Vec<u8>)
+	{
+		if value.len() < self.required_len()
+		{
+			value.resize(self.required_len(), 0);
+		}
+
+		match self {
+			BitOp::Set(bit) => value[byte(*bit)] |= mask(*bit),
+			BitOp::Clear(bit) => {
+				if let Some(b) = value.get_mut(byte(*bit))
+				{
+					*b &= !mask(*bit);
+				}
+			},
+			BitOp::Toggle(bit) => value[byte(*bit)] ^= mask(*bit),
+			BitOp::SetMask(bits) => {
+				for (b, m) in value.iter_mut().zip(bits)
+				{
+					*b |= m;
+				}
+			},
+		}
+	}
+}
+
+/// Whether `bit` is set in `value`.
+pub fn test(value : &[u8], bit : u32) -> bool
+{
+	value.get(byte(bit)).map_or(false, |b| b & mask(bit) != 0)
+}
+
+fn byte(bit : u32) -> usize
+{
+	bit as usize / 8
+}
+
+fn mask(bit : u32) -> u8
+{
+	1 << (bit % 8)
+}
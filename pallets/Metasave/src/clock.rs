@@ -0,0 +1,89 @@
+//! In-world clocks, computed from the chain's timestamp instead of being written every tick.
+//!
+//! All in-world durations are in in-world milliseconds. A clock at `speed` 1000 runs in real time;
+//! at 60_000 an in-world minute passes every real second.
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::UniqueSaturatedInto, RuntimeDebug};
+
+/// A named part of the in-world day, e.g. night.
+#[derive(Encode, Decode, RuntimeDebug, Clone, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+pub struct ClockPhase<Name> {
+	pub name: Name,
+	/// The time of day the phase starts at. It lasts until the next phase starts.
+	pub start: u64,
+}
+
+/// How a game world's time relates to the chain's.
+#[derive(Encode, Decode, RuntimeDebug, Clone, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+pub struct WorldClock<Moment, Phases> {
+	/// The timestamp at which in-world time was 0.
+	pub epoch: Moment,
+	/// In-world milliseconds passing per thousand real milliseconds.
+	pub speed: u32,
+	/// Length of an in-world day.
+	pub day_length: u64,
+	/// The phases of the day, by the time of day they start at. The last phase of a day carries
+	/// on into the next until its first phase starts.
+	pub phases: Phases,
+}
+
+/// A reading of a world clock.
+#[derive(Encode, Decode, RuntimeDebug, Clone, TypeInfo, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorldTime<Name> {
+	/// In-world time since the epoch.
+	pub elapsed: u64,
+	/// Number of whole in-world days since the epoch.
+	pub day: u64,
+	/// In-world time since the start of the current day.
+	pub time_of_day: u64,
+	/// The current phase, if the clock has any.
+	pub phase: Option<Name>,
+}
+
+impl<Moment, Phases> WorldClock<Moment, Phases> {
+	/// Whether the clock moves, and its phases start within the day in strictly increasing order.
+	pub fn is_valid<Name>(&self) -> bool
+	where
+		Phases: AsRef<[ClockPhase<Name>]>,
+	{
+		let phases = self.phases.as_ref();
+
+		self.speed > 0
+			&& self.day_length > 0
+			&& phases.iter().all(|phase| phase.start < self.day_length)
+			&& phases.windows(2).all(|pair| pair[0].start < pair[1].start)
+	}
+
+	/// Read the clock at timestamp `now`. Before the epoch, the clock reads 0.
+	pub fn read<Name: Clone>(&self, now : Moment) -> WorldTime<Name>
+	where
+		Moment: UniqueSaturatedInto<u64> + Copy,
+		Phases: AsRef<[ClockPhase<Name>]>,
+	{
+		let now : u64 = now.unique_saturated_into();
+		let epoch : u64 = self.epoch.unique_saturated_into();
+
+		let real = now.saturating_sub(epoch) as u128;
+		let elapsed = (real * self.speed as u128 / 1000).min(u64::MAX as u128) as u64;
+
+		let day_length = self.day_length.max(1);
+		let time_of_day = elapsed % day_length;
+
+		let phases = self.phases.as_ref();
+		let phase = phases.iter()
+			.rev()
+			.find(|phase| phase.start <= time_of_day)
+			.or_else(|| phases.last())
+			.map(|phase| phase.name.clone());
+
+		WorldTime {
+			elapsed,
+			day: elapsed / day_length,
+			time_of_day,
+			phase,
+		}
+	}
+}
@@ -14,36 +14,531 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod bits;
+
+pub mod clock;
+
+pub mod migrations;
+
+pub mod numeric;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use sp_runtime::traits::MaybeDisplay;
-	use sp_runtime::traits::AtLeast32Bit;	
+	use sp_runtime::traits::AtLeast32Bit;
+	use sp_runtime::traits::{Hash, One, Zero};	
 	use frame_support::dispatch::fmt::Debug;
-	use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+	use frame_support::{
+		dispatch::DispatchResult, pallet_prelude::*, sp_io::KillStorageResult, transactional,
+		CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+	};
 	use frame_system::pallet_prelude::*;
-	use sp_std::prelude::*;
+	use sp_std::{cmp::Ordering, prelude::*};
+	use crate::bits::{self, BitOp};
+	use crate::clock::{ClockPhase, WorldClock, WorldTime};
+	use crate::numeric::{self, IntKind, Number, NumericError, NumericOp, OverflowPolicy, Threshold};
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(8);
+
+	/// What an authority is allowed to do within a game.
+	#[derive(Encode, Decode, Debug, Clone, Copy, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	pub enum Role {
+		/// Full control of the game. Every registered game has exactly one owner, and ownership
+		/// only changes hands through `transfer_ownership`/`accept_ownership`.
+		Owner,
+		/// Writes both routes and manages the game's metadata, writers and moderators.
+		Admin,
+		/// Writes both routes.
+		InternalWriter,
+		/// Writes the External route only.
+		ExternalWriter,
+		/// Removes entries from either route, but cannot write them.
+		Moderator,
+	}
+
+	impl Role {
+		/// Whether the role may create or change entries on `route`.
+		pub fn can_write(&self, route : Route) -> bool
+		{
+			match self {
+				Role::Owner | Role::Admin | Role::InternalWriter => true,
+				Role::ExternalWriter => route == Route::External,
+				Role::Moderator => false,
+			}
+		}
+
+		/// Whether the role may remove entries from `route`.
+		pub fn can_remove(&self, route : Route) -> bool
+		{
+			*self == Role::Moderator || self.can_write(route)
+		}
+
+		/// Whether the role may change the game itself: metadata and authorities.
+		pub fn is_manager(&self) -> bool
+		{
+			matches!(self, Role::Owner | Role::Admin)
+		}
+
+		/// Whether the role may grant, change or revoke an authority holding `other`.
+		pub fn can_manage(&self, other : Role) -> bool
+		{
+			match self {
+				Role::Owner => other != Role::Owner,
+				Role::Admin => !matches!(other, Role::Owner | Role::Admin),
+				_ => false,
+			}
+		}
+	}
 
+	/// What an authorized call is about to do to an entry.
+	#[derive(Clone, Copy, PartialEq)]
+	pub(super) enum Action {
+		Write,
+		Remove,
+	}
+
+	/// One step of a `batch` call. Every step targets the batch's game.
 	#[derive(Encode, Decode, Debug, Clone, TypeInfo, PartialEq)]
-	pub enum Access {
-		External,
-		InternalExternal,
+	pub enum BatchOp<AccountId, BlockNumber> {
+		/// See `world_update_data_record`.
+		WorldUpdate { route : Route, entry : DataEntry, expected_revision : Option<u32>, expires_at : Option<BlockNumber> },
+		/// See `world_remove_data_record`.
+		WorldRemove { route : Route, key : Skey, expected_revision : Option<u32> },
+		/// See `world_numeric_op`.
+		WorldMod { route : Route, key : Skey, kind : IntKind, op : NumericOp, operand : Sval, overflow : OverflowPolicy, expected_revision : Option<u32> },
+		/// See `user_update_data_record`.
+		UserUpdate { user : AccountId, route : Route, entry : DataEntry, expected_revision : Option<u32>, expires_at : Option<BlockNumber> },
+		/// See `user_remove_data_record`.
+		UserRemove { user : AccountId, route : Route, key : Skey, expected_revision : Option<u32> },
+		/// See `user_numeric_op`.
+		UserMod { user : AccountId, route : Route, key : Skey, kind : IntKind, op : NumericOp, operand : Sval, overflow : OverflowPolicy, expected_revision : Option<u32> },
 	}
 
-	impl Default for Access {
-		fn default() -> Self {
-			Access::External
+	impl<AccountId, BlockNumber> BatchOp<AccountId, BlockNumber> {
+		pub fn route(&self) -> Route
+		{
+			match self {
+				BatchOp::WorldUpdate { route, .. } |
+				BatchOp::WorldRemove { route, .. } |
+				BatchOp::WorldMod { route, .. } |
+				BatchOp::UserUpdate { route, .. } |
+				BatchOp::UserRemove { route, .. } |
+				BatchOp::UserMod { route, .. } => *route,
+			}
+		}
+
+		pub(super) fn action(&self) -> Action
+		{
+			match self {
+				BatchOp::WorldRemove { .. } | BatchOp::UserRemove { .. } => Action::Remove,
+				_ => Action::Write,
+			}
 		}
 	}
 
-	#[derive(Encode, Decode, Debug, Clone, Copy, TypeInfo, PartialEq)]
+	#[derive(Encode, Decode, Debug, Clone, Copy, TypeInfo, PartialEq, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 	pub enum Route {
 		External = 0,
 		Internal = 1,
 	}
 
+	/// How the bytes of an entry value are laid out.
+	#[derive(Encode, Decode, Debug, Clone, Copy, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	pub enum ValueKind {
+		/// A single byte, `0` or `1`.
+		Bool,
+		/// A little-endian integer.
+		Int(IntKind),
+		/// A little-endian `i64` holding the number multiplied by `10^decimals`.
+		Fixed { decimals : u8 },
+		/// UTF-8 text.
+		String,
+		/// Raw bytes.
+		Bytes,
+		/// Back-to-back little-endian integers of a single kind.
+		List(IntKind),
+	}
+
+	/// A world or user entry value with its revision and who last wrote it, and when.
+	///
+	/// The revision starts at 1 when the entry is created and grows by one with every write, so
+	/// writers can detect that an entry changed since they read it. An absent entry is revision 0.
+	#[derive(Encode, Decode, Debug, Clone, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct Entry<Value, AccountId, BlockNumber, Moment> {
+		pub value: Value,
+		pub revision: u32,
+		/// The account that last wrote the value, or `None` if the value was written before the
+		/// pallet recorded writers.
+		pub writer: Option<AccountId>,
+		/// The block the value was last written in.
+		pub block: BlockNumber,
+		/// The timestamp of that block.
+		pub timestamp: Moment,
+	}
+
+	/// The runtime's timestamp type.
+	pub type MomentOf<T> = <T as pallet_timestamp::Config>::Moment;
+
+	/// An `Entry` as stored on chain.
+	pub type StoredEntry<T> = Entry<BoundedValue<T>, <T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber, MomentOf<T>>;
+
+	/// Restrictions on writing an entry, set by the game's owner or admins.
+	#[derive(Encode, Decode, Debug, Clone, Copy, Default, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	pub struct EntryFlags {
+		/// The entry can be written once and never changed or removed afterwards. Cannot be
+		/// cleared once set.
+		pub write_once: bool,
+		/// The entry cannot be written or removed until it is unlocked.
+		pub locked: bool,
+	}
+
+	/// An entry waiting in the expiry queue.
+	#[derive(Encode, Decode, Debug, Clone, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	pub struct ExpiringEntry<GameID, AccountId, Key> {
+		pub game: GameID,
+		/// The player whose entry expires, or `None` for a world entry.
+		pub user: Option<AccountId>,
+		pub route: Route,
+		pub key: Key,
+	}
+
+	/// An `ExpiringEntry` as stored on chain.
+	pub type ExpiringEntryOf<T> = ExpiringEntry<<T as Config>::GameID, <T as frame_system::Config>::AccountId, BoundedKey<T>>;
+
+	/// When a scheduled change takes effect.
+	#[derive(Encode, Decode, Debug, Clone, Copy, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	pub enum ScheduleTime<BlockNumber, Moment> {
+		/// At the start of the block.
+		Block(BlockNumber),
+		/// At the start of the first block whose parent's timestamp is at least this one.
+		Timestamp(Moment),
+	}
+
+	/// A `ScheduleTime` of the runtime's block number and timestamp types.
+	pub type ScheduleTimeOf<T> = ScheduleTime<<T as frame_system::Config>::BlockNumber, MomentOf<T>>;
+
+	/// A scheduled change while it is in effect.
+	#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ActiveChange<T: Config> {
+		/// The block the change was applied in.
+		pub started: T::BlockNumber,
+		/// The value the change replaced, restored when it ends, or `None` if the entry was absent.
+		pub previous: Option<BoundedValue<T>>,
+	}
+
+	/// A world entry write an authority scheduled in advance.
+	#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ScheduledChange<T: Config> {
+		/// The authority that scheduled the change, recorded as the writer of the entry. The change
+		/// only applies while they can still write its route.
+		pub creator: T::AccountId,
+		pub route: Route,
+		pub key: BoundedKey<T>,
+		pub value: BoundedValue<T>,
+		/// When the value is next written.
+		pub start: ScheduleTimeOf<T>,
+		/// When the entry is next reverted to its previous value, if the change is temporary.
+		pub end: Option<ScheduleTimeOf<T>>,
+		/// Repeat the change every this many blocks, counted from when it was last applied.
+		pub period: Option<T::BlockNumber>,
+		/// Set while the change is in effect and waiting for its `end`.
+		pub active: Option<ActiveChange<T>>,
+	}
+
+	impl<T: Config> ScheduledChange<T> {
+		/// When the change is next due to fire.
+		pub fn next_time(&self) -> ScheduleTimeOf<T>
+		{
+			match (&self.active, self.end) {
+				(Some(_), Some(end)) => end,
+				_ => self.start,
+			}
+		}
+	}
+
+	/// A rule writing a world entry of one game when an integer world entry of another crosses a
+	/// threshold.
+	#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct TriggerRule<T: Config> {
+		/// The game the rule writes to, which the rule belongs to.
+		pub game: T::GameID,
+		/// The rule's id within its game.
+		pub id: u32,
+		/// The authority that registered the rule, recorded as the writer of the entry. The rule
+		/// only writes while they can still write its route.
+		pub creator: T::AccountId,
+		/// How to read the watched entry's value.
+		pub kind: IntKind,
+		pub threshold: Threshold,
+		/// The entry the rule writes, and the value it writes.
+		pub route: Route,
+		pub key: BoundedKey<T>,
+		pub value: BoundedValue<T>,
+	}
+
+	/// Which end of a leaderboard the best score is at.
+	#[derive(Encode, Decode, Debug, Clone, Copy, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	pub enum RankOrder {
+		/// Lowest score first, e.g. speedrun times.
+		Ascending,
+		/// Highest score first, e.g. kills.
+		Descending,
+	}
+
+	impl RankOrder {
+		/// Whether `score` ranks strictly above `other`.
+		pub fn ranks_above(&self, score : Number, other : Number) -> bool
+		{
+			match self {
+				RankOrder::Ascending => score < other,
+				RankOrder::Descending => score > other,
+			}
+		}
+	}
+
+	/// The best players of a game by the integer value of one of its user keys.
+	#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Leaderboard<T: Config> {
+		/// How to read the key's values.
+		pub kind: IntKind,
+		pub order: RankOrder,
+		/// How many players the board ranks, at most `MaxLeaderboardSize`.
+		pub size: u32,
+		/// The best players and their scores, best first. Players with equal scores keep the
+		/// order they reached the score in.
+		pub ranking: BoundedVec<(T::AccountId, Number), T::MaxLeaderboardSize>,
+		/// The best score of a player pushed off or kept off the board, if any. Players scoring
+		/// below it may rank below a player the board no longer knows about, so they aren't ranked.
+		pub cutoff: Option<Number>,
+	}
+
+	impl<T: Config> Leaderboard<T> {
+		/// Move `who` to the rank `value` earns them, or off the board if there is no value or it
+		/// doesn't read as the board's kind. Returns whether the ranking changed.
+		///
+		/// Only players whose entry is written are ranked: a player pushed off the board comes
+		/// back once their entry is written again, not when a player above them drops out. A
+		/// player whose score drops below the `cutoff` leaves the board, so it may rank fewer
+		/// than `size` players but never ranks them wrongly.
+		pub fn rank(&mut self, who : &T::AccountId, value : Option<&[u8]>) -> bool
+		{
+			let mut ranking = self.ranking.clone().into_inner();
+			let cutoff = self.cutoff;
+
+			if let Some(index) = ranking.iter().position(|(player, _)| player == who)
+			{
+				ranking.remove(index);
+			}
+
+			if let Some(score) = value.and_then(|value| self.kind.decode(value))
+			{
+				let position = ranking.iter()
+					.position(|(_, other)| self.order.ranks_above(score, *other))
+					.unwrap_or(ranking.len());
+
+				if self.cutoff.map_or(false, |cutoff| self.order.ranks_above(cutoff, score))
+				{
+					// Left off the board, without moving the cutoff: it is already better.
+				}
+				else if position < self.size as usize
+				{
+					ranking.insert(position, (who.clone(), score));
+
+					if ranking.len() > self.size as usize
+					{
+						let (_, dropped) = ranking.remove(self.size as usize);
+						self.discard(dropped);
+					}
+				}
+				else
+				{
+					self.discard(score);
+				}
+			}
+
+			if ranking[..] == self.ranking[..] && self.cutoff == cutoff
+			{
+				return false;
+			}
+
+			// Cannot fail: the ranking is no longer than `size`, which is at most `MaxLeaderboardSize`.
+			self.ranking = BoundedVec::try_from(ranking).unwrap_or_default();
+
+			true
+		}
+
+		/// Raise the cutoff to `score` of a player left off the board, if it ranks above it.
+		fn discard(&mut self, score : Number)
+		{
+			if self.cutoff.map_or(true, |cutoff| self.order.ranks_above(score, cutoff))
+			{
+				self.cutoff = Some(score);
+			}
+		}
+	}
+
+	/// A phase name as stored on chain.
+	pub type PhaseName<T> = BoundedVec<u8, <T as Config>::MaxPhaseNameLen>;
+
+	/// A `WorldClock` as stored on chain.
+	pub type WorldClockOf<T> = WorldClock<MomentOf<T>, BoundedVec<ClockPhase<PhaseName<T>>, <T as Config>::MaxClockPhases>>;
+
+	/// What happened to an entry, as recorded in the change log.
+	#[derive(Encode, Decode, Debug, Clone, Copy, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub enum ChangeOp {
+		/// The entry was created or overwritten.
+		Write,
+		/// The entry was removed.
+		Remove,
+	}
+
+	/// One change to a world or user entry of a game.
+	#[derive(Encode, Decode, Debug, Clone, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct ChangeRecord<Key, AccountId, BlockNumber> {
+		/// Position in the game's change log. The first change of a game is 1.
+		pub seq: u64,
+		/// The block the change happened in.
+		pub block: BlockNumber,
+		pub route: Route,
+		/// The player whose entry changed, or `None` for a world entry.
+		pub user: Option<AccountId>,
+		pub key: Key,
+		pub op: ChangeOp,
+	}
+
+	/// A `ChangeRecord` as stored on chain.
+	pub type ChangeRecordOf<T> = ChangeRecord<BoundedKey<T>, <T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+	/// A value an entry held, as kept in its history.
+	#[derive(Encode, Decode, Debug, Clone, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+	pub struct HistoryEntry<Value, AccountId, BlockNumber> {
+		pub value: Value,
+		/// The entry's revision while it held the value.
+		pub revision: u32,
+		/// The block the value was written in.
+		pub block: BlockNumber,
+		/// The account that wrote the value.
+		pub writer: AccountId,
+	}
+
+	/// A `HistoryEntry` as stored on chain.
+	pub type HistoryOf<T> = HistoryEntry<BoundedValue<T>, <T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+	/// The most recent values of an entry, oldest first.
+	pub type History<T> = BoundedVec<HistoryOf<T>, <T as Config>::MaxHistoryDepth>;
+
+	/// An entry value as reported in events.
+	#[derive(Encode, Decode, Debug, Clone, TypeInfo, PartialEq, Eq)]
+	pub enum EventValue<Hash> {
+		/// The value itself.
+		Raw(Vec<u8>),
+		/// The hash of a value longer than `MaxEventValueLen`.
+		Hashed(Hash),
+	}
+
+	/// How a write changed an entry, as reported in events.
+	#[derive(Encode, Decode, Debug, Clone, TypeInfo, PartialEq, Eq)]
+	pub struct ValueChange<Hash> {
+		pub key: Skey,
+		/// The value before the write, or `None` if the entry was created.
+		pub old: Option<EventValue<Hash>>,
+		/// The value after the write, or `None` if the entry was removed.
+		pub new: Option<EventValue<Hash>>,
+		/// The entry's revision after the write, or the removed revision.
+		pub revision: u32,
+	}
+
+	/// A `ValueChange` of the runtime's hash type.
+	pub type Change<T> = ValueChange<<T as frame_system::Config>::Hash>;
+
+	/// The shape a game promises the value of one of its keys will have.
+	#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ValueSchema<T: Config> {
+		pub kind: ValueKind,
+		/// Smallest accepted number; for strings and bytes the shortest accepted length, and for
+		/// lists the fewest accepted elements. `Fixed` bounds are in raw, scaled units.
+		pub min: Option<i128>,
+		/// Largest accepted number, length or element count, like `min`.
+		pub max: Option<i128>,
+		/// The value readers should assume while the entry is absent.
+		pub default: Option<BoundedValue<T>>,
+	}
+
+	impl<T: Config> ValueSchema<T> {
+		/// Whether `value` is laid out as `kind` and falls within `min..=max`.
+		pub fn accepts(&self, value : &[u8]) -> bool
+		{
+			let measure = match self.kind {
+				ValueKind::Bool => return value.len() == 1 && value[0] <= 1,
+				ValueKind::Int(kind) => match kind.decode(value) {
+					Some(number) => number,
+					None => return false,
+				},
+				ValueKind::Fixed { .. } => match IntKind::I64.decode(value) {
+					Some(number) => number,
+					None => return false,
+				},
+				ValueKind::String => match sp_std::str::from_utf8(value) {
+					Ok(_) => Number::Unsigned(value.len() as u128),
+					Err(_) => return false,
+				},
+				ValueKind::Bytes => Number::Unsigned(value.len() as u128),
+				ValueKind::List(kind) => match value.len() % kind.width() {
+					0 => Number::Unsigned((value.len() / kind.width()) as u128),
+					_ => return false,
+				},
+			};
+
+			self.min.map_or(true, |min| measure.cmp_i128(min) != Ordering::Less) &&
+				self.max.map_or(true, |max| measure.cmp_i128(max) != Ordering::Greater)
+		}
+
+		/// Whether the schema is consistent with itself, including its default.
+		pub fn is_valid(&self) -> bool
+		{
+			let decimals_ok = match self.kind {
+				ValueKind::Fixed { decimals } => decimals <= 18,
+				_ => true,
+			};
+
+			let range_ok = match (self.min, self.max) {
+				(Some(min), Some(max)) => min <= max,
+				_ => true,
+			};
+
+			decimals_ok && range_ok && self.default.as_ref().map_or(true, |value| self.accepts(value))
+		}
+	}
+
+	/// What a registered game is, as shown to players and other games.
+	#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct GameInfo<T: Config> {
+		/// The account that registered the game.
+		pub owner: T::AccountId,
+		pub name: BoundedVec<u8, T::MaxNameLen>,
+		pub description: BoundedVec<u8, T::MaxDescriptionLen>,
+		pub website: BoundedVec<u8, T::MaxWebsiteLen>,
+		/// Hash of the game's icon, which is hosted off chain.
+		pub icon: Option<T::Hash>,
+		pub genres: BoundedVec<BoundedVec<u8, T::MaxGenreLen>, T::MaxGenres>,
+		/// The block the game was registered in.
+		pub created_at: T::BlockNumber,
+	}
+
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: frame_system::Config + pallet_timestamp::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
@@ -58,407 +553,3134 @@ pub mod pallet {
 			+ AtLeast32Bit
 			+ Copy
 			+ Encode
-			+ Decode;
+			+ Decode
+			+ MaxEncodedLen;
+
+		/// The maximum length of a data entry key, in bytes.
+		#[pallet::constant]
+		type MaxKeyLen: Get<u32>;
+
+		/// The maximum length of a data entry value, in bytes.
+		#[pallet::constant]
+		type MaxValueLen: Get<u32>;
+
+		/// The maximum number of entries in a single `Route::External` record.
+		#[pallet::constant]
+		type MaxExternalEntries: Get<u32>;
+
+		/// The maximum number of entries in a single `Route::Internal` record.
+		#[pallet::constant]
+		type MaxInternalEntries: Get<u32>;
+
+		/// The maximum length of a game's name, in bytes.
+		#[pallet::constant]
+		type MaxNameLen: Get<u32>;
+
+		/// The maximum length of a game's description, in bytes.
+		#[pallet::constant]
+		type MaxDescriptionLen: Get<u32>;
+
+		/// The maximum length of a game's website, in bytes.
+		#[pallet::constant]
+		type MaxWebsiteLen: Get<u32>;
+
+		/// The maximum number of genre tags a game can have.
+		#[pallet::constant]
+		type MaxGenres: Get<u32>;
+
+		/// The maximum length of a single genre tag, in bytes.
+		#[pallet::constant]
+		type MaxGenreLen: Get<u32>;
+
+		/// The maximum number of operations in a single `batch` call.
+		#[pallet::constant]
+		type MaxBatchOps: Get<u32>;
+
+		/// Values longer than this, in bytes, are reported in events by their hash.
+		#[pallet::constant]
+		type MaxEventValueLen: Get<u32>;
+
+		/// The most values a game may keep in the history of each of its entries.
+		#[pallet::constant]
+		type MaxHistoryDepth: Get<u32>;
+
+		/// The most history values a single `set_history_depth` call deletes.
+		#[pallet::constant]
+		type MaxHistoryRemovals: Get<u32>;
+
+		/// How many of its most recent changes each game's change log keeps.
+		#[pallet::constant]
+		type MaxChangeLogLen: Get<u32>;
+
+		/// The most entries that can be set to expire at the same block.
+		#[pallet::constant]
+		type MaxExpiriesPerBlock: Get<u32>;
+
+		/// The most entries of a game that can be set to expire at once.
+		#[pallet::constant]
+		type MaxExpiries: Get<u32>;
+
+		/// The most changes a game can have scheduled at once.
+		#[pallet::constant]
+		type MaxSchedules: Get<u32>;
+
+		/// The most scheduled changes due at the same block or in the same time slot, and the most
+		/// timestamp-based changes fired in a single block.
+		#[pallet::constant]
+		type MaxSchedulesPerBlock: Get<u32>;
+
+		/// The length of the time slots timestamp-based changes are queued by, usually the block
+		/// time.
+		#[pallet::constant]
+		type TimedScheduleSlot: Get<MomentOf<Self>>;
+
+		/// The most time slots checked for due changes in a single block, so a chain catching up
+		/// after a stall spreads the work over several blocks.
+		#[pallet::constant]
+		type MaxTimedSlotsPerBlock: Get<u32>;
+
+		/// The most blocks, or time slots, the next firing of a scheduled change is pushed back
+		/// while the queues it would wait in are full.
+		#[pallet::constant]
+		type MaxScheduleDelay: Get<u32>;
+
+		/// The most leaderboards a game can have.
+		#[pallet::constant]
+		type MaxLeaderboards: Get<u32>;
+
+		/// The most players a leaderboard can rank.
+		#[pallet::constant]
+		type MaxLeaderboardSize: Get<u32>;
+
+		/// The most trigger rules a game can have registered.
+		#[pallet::constant]
+		type MaxTriggers: Get<u32>;
+
+		/// The most trigger rules watching the same entry. Every write of a watched entry pays for
+		/// this many rules.
+		#[pallet::constant]
+		type MaxTriggersPerKey: Get<u32>;
+
+		/// The most trigger rules a single game can have watching the same entry, so no one game
+		/// takes up all of its `MaxTriggersPerKey` rules.
+		#[pallet::constant]
+		type MaxGameTriggersPerKey: Get<u32>;
+
+		/// The most phases a world clock's day can have.
+		#[pallet::constant]
+		type MaxClockPhases: Get<u32>;
+
+		/// The maximum length of a clock phase's name, in bytes.
+		#[pallet::constant]
+		type MaxPhaseNameLen: Get<u32>;
+
+		/// The most weight `on_initialize` spends removing expired entries. Entries it has no
+		/// budget left for are removed in the following blocks.
+		#[pallet::constant]
+		type ExpiryWeightBudget: Get<Weight>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	#[pallet::generate_storage_info]
+	pub struct Pallet<T>(_);
+
+	pub(super) type Skey = Vec<u8>;
+	pub(super) type Sval = Vec<u8>;
+	pub(super) type DataEntry = (Skey,Sval);
+	pub(super) type DataRecord = Vec<DataEntry>;
+
+	/// A data entry key as stored on chain.
+	pub type BoundedKey<T> = BoundedVec<u8, <T as Config>::MaxKeyLen>;
+	/// A data entry value as stored on chain.
+	pub type BoundedValue<T> = BoundedVec<u8, <T as Config>::MaxValueLen>;
+
+	/// Every registered game and its metadata.
+	#[pallet::storage]
+	pub(super) type Games<T: Config> = StorageMap<_, Twox64Concat, T::GameID, GameInfo<T>, OptionQuery>;
+
+	/// World data, stored one entry per key so single-key reads and writes don't touch the rest
+	/// of the record.
+	#[pallet::storage]
+	pub(super) type WorldData<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		StoredEntry<T>,
+		OptionQuery,
+	>;
+
+	/// Per-player data, stored one entry per key. Keyed by game first so every player of a game
+	/// shares a storage prefix.
+	#[pallet::storage]
+	pub(super) type UserData<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		StoredEntry<T>,
+		OptionQuery,
+	>;
+
+	/// Schemas world entries must conform to. Public so other games can decode the values.
+	#[pallet::storage]
+	pub(super) type WorldSchemas<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		ValueSchema<T>,
+		OptionQuery,
+	>;
+
+	/// Schemas user entries must conform to, shared by every player of the game.
+	#[pallet::storage]
+	pub(super) type UserSchemas<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		ValueSchema<T>,
+		OptionQuery,
+	>;
+
+	/// Number of entries in each world record, checked against the route's entry limit.
+	#[pallet::storage]
+	pub(super) type WorldRecordLen<T: Config> = StorageDoubleMap<_, Twox64Concat, T::GameID, Twox64Concat, Route, u32, ValueQuery>;
+
+	/// Number of entries in each player record, checked against the route's entry limit.
+	#[pallet::storage]
+	pub(super) type UserRecordLen<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Twox64Concat, Route>,
+		),
+		u32,
+		ValueQuery,
+	>;
+
+	/// Players holding data in a game, with the number of entries they hold across both routes.
+	#[pallet::storage]
+	pub(super) type GamePlayers<T: Config> = StorageDoubleMap<_, Twox64Concat, T::GameID, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// Reverse index of `GamePlayers`: the games a player holds data in.
+	#[pallet::storage]
+	pub(super) type PlayerGames<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, T::GameID, (), OptionQuery>;
+
+	/// The access each authority of a game holds, so a permission check is a single read.
+	#[pallet::storage]
+	pub(super) type Authorities<T: Config> = StorageDoubleMap<_, Twox64Concat, T::GameID, Blake2_128Concat, T::AccountId, Role, OptionQuery>;
+
+	/// Accounts the owner has offered ownership to, waiting for them to accept.
+	#[pallet::storage]
+	pub(super) type PendingOwner<T: Config> = StorageMap<_, Twox64Concat, T::GameID, T::AccountId, OptionQuery>;
+
+	/// Reverse index of `Authorities`: the games an account is an authority of.
+	#[pallet::storage]
+	pub(super) type AuthorityGames<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, T::GameID, (), OptionQuery>;
+
+	/// The sequence number of the latest change of every game.
+	#[pallet::storage]
+	pub(super) type ChangeSeq<T: Config> = StorageMap<_, Twox64Concat, T::GameID, u64, ValueQuery>;
+
+	/// The revision each removed world entry was at, so the entry carries on from it if recreated.
+	#[pallet::storage]
+	pub(super) type WorldRevisions<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		u32,
+		ValueQuery,
+	>;
+
+	/// The revision each removed user entry was at. See `WorldRevisions`.
+	#[pallet::storage]
+	pub(super) type UserRevisions<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		u32,
+		ValueQuery,
+	>;
+
+	/// The last `MaxChangeLogLen` changes of every game, by sequence number.
+	#[pallet::storage]
+	pub(super) type ChangeLog<T: Config> = StorageDoubleMap<_, Twox64Concat, T::GameID, Twox64Concat, u64, ChangeRecordOf<T>, OptionQuery>;
+
+	/// How many values each entry of a game keeps in its history. Games keep none unless they opt in.
+	#[pallet::storage]
+	pub(super) type HistoryDepth<T: Config> = StorageMap<_, Twox64Concat, T::GameID, u32, ValueQuery>;
+
+	/// Games whose history `set_history_depth` has started but not finished deleting.
+	#[pallet::storage]
+	pub(super) type HistoryClearing<T: Config> = StorageMap<_, Twox64Concat, T::GameID, (), OptionQuery>;
+
+	/// The last `HistoryDepth` values of every world entry, including the current one.
+	#[pallet::storage]
+	pub(super) type WorldHistory<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		History<T>,
+		ValueQuery,
+	>;
+
+	/// The last `HistoryDepth` values of every user entry, including the current one.
+	#[pallet::storage]
+	pub(super) type UserHistory<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		History<T>,
+		ValueQuery,
+	>;
+
+	/// Write restrictions on world entries. Flags can be set before the entry is first written.
+	#[pallet::storage]
+	pub(super) type WorldEntryFlags<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		EntryFlags,
+		OptionQuery,
+	>;
+
+	/// Write restrictions on user entries, set per player.
+	#[pallet::storage]
+	pub(super) type UserEntryFlags<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		EntryFlags,
+		OptionQuery,
+	>;
+
+	/// The block each expiring world entry is removed at.
+	#[pallet::storage]
+	pub(super) type WorldExpiry<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// The block each expiring user entry is removed at.
+	#[pallet::storage]
+	pub(super) type UserExpiry<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// The entries due to expire at each block. An entry leaves the queue as soon as its expiry is
+	/// changed or cleared.
+	#[pallet::storage]
+	pub(super) type ExpiryQueue<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, BoundedVec<ExpiringEntryOf<T>, T::MaxExpiriesPerBlock>, ValueQuery>;
+
+	/// Number of entries each game has set to expire, checked against `MaxExpiries`.
+	#[pallet::storage]
+	pub(super) type ExpiryCount<T: Config> = StorageMap<_, Twox64Concat, T::GameID, u32, ValueQuery>;
+
+	/// The oldest block whose expiry queue may not have been processed yet.
+	#[pallet::storage]
+	pub(super) type ExpiryCursor<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// The id the next scheduled change of each game gets.
+	#[pallet::storage]
+	pub(super) type NextScheduleId<T: Config> = StorageMap<_, Twox64Concat, T::GameID, u32, ValueQuery>;
+
+	/// Number of changes each game has scheduled, checked against `MaxSchedules`.
+	#[pallet::storage]
+	pub(super) type ScheduleCount<T: Config> = StorageMap<_, Twox64Concat, T::GameID, u32, ValueQuery>;
+
+	/// Every scheduled world change, by game and id.
+	#[pallet::storage]
+	pub(super) type Schedules<T: Config> = StorageDoubleMap<_, Twox64Concat, T::GameID, Twox64Concat, u32, ScheduledChange<T>, OptionQuery>;
+
+	/// The block-based scheduled changes due at each block.
+	///
+	/// Like `ExpiryQueue`, entries of rescheduled changes are skipped.
+	#[pallet::storage]
+	pub(super) type BlockSchedules<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, BoundedVec<(T::GameID, u32), T::MaxSchedulesPerBlock>, ValueQuery>;
+
+	/// The timestamp-based scheduled changes due in each time slot, with the time they are due at.
+	///
+	/// Like `BlockSchedules`, entries of rescheduled changes are skipped.
+	#[pallet::storage]
+	pub(super) type TimedSchedules<T: Config> = StorageMap<_, Twox64Concat, MomentOf<T>, BoundedVec<(MomentOf<T>, T::GameID, u32), T::MaxSchedulesPerBlock>, ValueQuery>;
+
+	/// The first time slot that may still have changes waiting to fire, or `None` if no change was
+	/// ever queued by timestamp.
+	#[pallet::storage]
+	pub(super) type TimedScheduleCursor<T: Config> = StorageValue<_, MomentOf<T>, OptionQuery>;
+
+	/// The leaderboards of every game, by the user key they rank players by. Public so other games
+	/// can read them.
+	#[pallet::storage]
+	pub(super) type Leaderboards<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, T::GameID>,
+			NMapKey<Twox64Concat, Route>,
+			NMapKey<Blake2_128Concat, BoundedKey<T>>,
+		),
+		Leaderboard<T>,
+		OptionQuery,
+	>;
+
+	/// Number of leaderboards each game has, checked against `MaxLeaderboards`.
+	#[pallet::storage]
+	pub(super) type LeaderboardCount<T: Config> = StorageMap<_, Twox64Concat, T::GameID, u32, ValueQuery>;
+
+	/// The trigger rules watching each external world entry, by the watched game and key.
+	#[pallet::storage]
+	pub(super) type Triggers<T: Config> = StorageDoubleMap<_, Twox64Concat, T::GameID, Blake2_128Concat, BoundedKey<T>, BoundedVec<TriggerRule<T>, T::MaxTriggersPerKey>, ValueQuery>;
+
+	/// The entry each trigger rule watches, by the rule's game and id.
+	#[pallet::storage]
+	pub(super) type TriggerWatches<T: Config> = StorageDoubleMap<_, Twox64Concat, T::GameID, Twox64Concat, u32, (T::GameID, BoundedKey<T>), OptionQuery>;
+
+	/// Number of trigger rules each game has registered, checked against `MaxTriggers`.
+	#[pallet::storage]
+	pub(super) type TriggerCount<T: Config> = StorageMap<_, Twox64Concat, T::GameID, u32, ValueQuery>;
+
+	/// The id the next trigger rule of each game gets.
+	#[pallet::storage]
+	pub(super) type NextTriggerId<T: Config> = StorageMap<_, Twox64Concat, T::GameID, u32, ValueQuery>;
+
+	/// The clock of every game that declared one. Public so other games can read it.
+	#[pallet::storage]
+	pub(super) type WorldClocks<T: Config> = StorageMap<_, Twox64Concat, T::GameID, WorldClockOf<T>, OptionQuery>;
+
+	// Pallets use events to inform users when important changes are made.
+	// https://docs.substrate.io/v3/runtime/events-and-errors
+	//
+	// Events are deposited with topics for their game, route and key; see `deposit_event`.
+	#[pallet::event]
+	pub enum Event<T: Config> {
+		// [Game world, route, signer, change]
+		WorldDataUpdated(T::GameID, Route, T::AccountId, Change<T>),
+
+		// [Game world, route, signer, change]
+		WorldDataRemoved(T::GameID, Route, T::AccountId, Change<T>),
+
+		// [Game world, route, signer, change]
+		WorldDataModified(T::GameID, Route, T::AccountId, Change<T>),
+
+		// [Game world, user, route, signer, change]
+		UserDataUpdated(T::GameID, T::AccountId, Route, T::AccountId, Change<T>),
+
+		// [Game world, user, route, signer, change]
+		UserDataRemoved(T::GameID, T::AccountId, Route, T::AccountId, Change<T>),
+
+		// [Game world, user, route, signer, change]
+		UserDataModified(T::GameID, T::AccountId, Route, T::AccountId, Change<T>),
+
+		// [Game world, route, signer, restored revision, change]
+		WorldDataRolledBack(T::GameID, Route, T::AccountId, u32, Change<T>),
+
+		// [Game world, user, route, signer, restored revision, change]
+		UserDataRolledBack(T::GameID, T::AccountId, Route, T::AccountId, u32, Change<T>),
+
+		// [Game world, route, signer, key]
+		WorldSchemaSet(T::GameID, Route, T::AccountId, Skey),
+
+		// [Game world, route, signer, key]
+		WorldSchemaRemoved(T::GameID, Route, T::AccountId, Skey),
+
+		// [Game world, route, signer, key]
+		UserSchemaSet(T::GameID, Route, T::AccountId, Skey),
+
+		// [Game world, route, signer, key]
+		UserSchemaRemoved(T::GameID, Route, T::AccountId, Skey),
+
+		// [Game world, user or None for world data, route, change]
+		EntryExpired(T::GameID, Option<T::AccountId>, Route, Change<T>),
+
+		// [Game world, schedule id, route, signer, key]
+		ChangeScheduled(T::GameID, u32, Route, T::AccountId, Skey),
+
+		// [Game world, schedule id, route, change]
+		ScheduledChangeApplied(T::GameID, u32, Route, Change<T>),
+
+		// [Game world, schedule id, route, change]
+		ScheduledChangeReverted(T::GameID, u32, Route, Change<T>),
+
+		// [Game world, schedule id, error]
+		ScheduledChangeFailed(T::GameID, u32, DispatchError),
+
+		// [Game world, schedule id, signer]
+		ScheduledChangeCancelled(T::GameID, u32, T::AccountId),
+
+		// [Game world, route, signer, key, flags]
+		WorldEntryFlagsSet(T::GameID, Route, T::AccountId, Skey, EntryFlags),
+
+		// [Game world, user, route, signer, key, flags]
+		UserEntryFlagsSet(T::GameID, T::AccountId, Route, T::AccountId, Skey, EntryFlags),
+
+		// [Game world, route, signer, key]
+		LeaderboardSet(T::GameID, Route, T::AccountId, Skey),
+
+		// [Game world, route, signer, key]
+		LeaderboardRemoved(T::GameID, Route, T::AccountId, Skey),
+
+		// [Game world, route, signer, key]
+		LeaderboardReseeded(T::GameID, Route, T::AccountId, Skey),
+
+		// [Game world, trigger id, watched game, signer]
+		TriggerRegistered(T::GameID, u32, T::GameID, T::AccountId),
+
+		// [Game world, trigger id, signer]
+		TriggerRemoved(T::GameID, u32, T::AccountId),
+
+		// [Game world, trigger id, route, change]
+		TriggerFired(T::GameID, u32, Route, Change<T>),
+
+		// [Game world, trigger id, error]
+		TriggerFailed(T::GameID, u32, DispatchError),
+
+		// [Game world, signer]
+		WorldClockSet(T::GameID, T::AccountId),
+
+		// [Game world, signer]
+		WorldClockRemoved(T::GameID, T::AccountId),
+
+		// [Game world, signer, number of operations]
+		BatchApplied(T::GameID, T::AccountId, u32),
+
+		// [Game world, history depth, signer]
+		HistoryDepthSet(T::GameID, u32, T::AccountId),
+
+		// [Game world, owner]
+		GameRegistered(T::GameID, T::AccountId),
+
+		// [Game world, signer]
+		GameInfoUpdated(T::GameID, T::AccountId),
+
+		// [Game world, new authority, role, signer]
+		AuthorityAdded(T::GameID, T::AccountId, Role, T::AccountId),
+
+		// [Game world, authority, new role, signer]
+		AuthorityChanged(T::GameID, T::AccountId, Role, T::AccountId),
+
+		// [Game world, removed authority, signer]
+		AuthorityRemoved(T::GameID, T::AccountId, T::AccountId),
+
+		// [Game world, current owner, proposed owner]
+		OwnershipTransferProposed(T::GameID, T::AccountId, T::AccountId),
+
+		// [Game world, owner]
+		OwnershipTransferCancelled(T::GameID, T::AccountId),
+
+		// [Game world, previous owner, new owner]
+		OwnershipTransferred(T::GameID, T::AccountId, T::AccountId),
 	}
 
-	#[pallet::pallet]
-	#[pallet::generate_store(pub(super) trait Store)]
-	pub struct Pallet<T>(_);
+	impl<T: Config> Event<T> {
+		/// The game, route and key the event is about, where it has them.
+		fn subject(&self) -> (T::GameID, Option<Route>, Option<&[u8]>)
+		{
+			match self {
+				Event::WorldDataUpdated(game, route, _, ValueChange { key, .. }) |
+				Event::WorldDataModified(game, route, _, ValueChange { key, .. }) |
+				Event::WorldDataRemoved(game, route, _, ValueChange { key, .. }) |
+				Event::UserDataUpdated(game, _, route, _, ValueChange { key, .. }) |
+				Event::UserDataModified(game, _, route, _, ValueChange { key, .. }) |
+				Event::UserDataRemoved(game, _, route, _, ValueChange { key, .. }) |
+				Event::WorldDataRolledBack(game, route, _, _, ValueChange { key, .. }) |
+				Event::EntryExpired(game, _, route, ValueChange { key, .. }) |
+				Event::ScheduledChangeApplied(game, _, route, ValueChange { key, .. }) |
+				Event::ScheduledChangeReverted(game, _, route, ValueChange { key, .. }) |
+				Event::ChangeScheduled(game, _, route, _, key) |
+				Event::TriggerFired(game, _, route, ValueChange { key, .. }) |
+				Event::LeaderboardSet(game, route, _, key) |
+				Event::LeaderboardRemoved(game, route, _, key) |
+				Event::LeaderboardReseeded(game, route, _, key) |
+				Event::UserDataRolledBack(game, _, route, _, _, ValueChange { key, .. }) |
+				Event::WorldSchemaSet(game, route, _, key) |
+				Event::WorldSchemaRemoved(game, route, _, key) |
+				Event::UserSchemaSet(game, route, _, key) |
+				Event::UserSchemaRemoved(game, route, _, key) |
+				Event::WorldEntryFlagsSet(game, route, _, key, _) |
+				Event::UserEntryFlagsSet(game, _, route, _, key, _) => (*game, Some(*route), Some(&key[..])),
+				Event::ScheduledChangeFailed(game, ..) |
+				Event::ScheduledChangeCancelled(game, ..) |
+				Event::TriggerRegistered(game, ..) |
+				Event::TriggerRemoved(game, ..) |
+				Event::TriggerFailed(game, ..) |
+				Event::WorldClockSet(game, ..) |
+				Event::WorldClockRemoved(game, ..) |
+				Event::BatchApplied(game, ..) |
+				Event::HistoryDepthSet(game, ..) |
+				Event::GameRegistered(game, ..) |
+				Event::GameInfoUpdated(game, ..) |
+				Event::AuthorityAdded(game, ..) |
+				Event::AuthorityChanged(game, ..) |
+				Event::AuthorityRemoved(game, ..) |
+				Event::OwnershipTransferProposed(game, ..) |
+				Event::OwnershipTransferCancelled(game, ..) |
+				Event::OwnershipTransferred(game, ..) => (*game, None, None),
+				Event::__Ignore(..) => unreachable!(),
+			}
+		}
+
+		/// The topics the event is deposited under. See `Pallet::game_topic` and friends.
+		pub fn topics(&self) -> Vec<T::Hash>
+		{
+			let (game, route, key) = self.subject();
+
+			let mut topics = sp_std::vec![<Pallet<T>>::game_topic(game)];
+			topics.extend(route.map(|route| <Pallet<T>>::route_topic(game, route)));
+			topics.extend(key.map(|key| <Pallet<T>>::key_topic(game, key)));
+
+			topics
+		}
+	}
+
+	// Errors inform users that something went wrong.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Error names should be descriptive.
+		NoneValue,
+		/// Errors should have helpful documentation associated with them.
+		StorageOverflow,
+
+		AlreadyRegisteredGame,
+
+		AlreadyRegisteredAuthority,
+
+		InvalidAuthority,
+		
+		InvalidAccess,
+
+		NotFound,
+
+		BadSize,
+
+		/// The entry key is longer than `MaxKeyLen`.
+		KeyTooLong,
+
+		/// The entry value is longer than `MaxValueLen`.
+		ValueTooLarge,
+
+		/// The record already holds the maximum number of entries for its route.
+		RecordFull,
+
+		/// The game has not been registered.
+		UnknownGame,
+
+		/// The game has authorities from before games were registered, but none that could own it.
+		/// Only root can register it.
+		GameHasAuthorities,
+
+		/// The game's owner cannot be removed or demoted; ownership must be transferred instead.
+		OwnerRequired,
+
+		/// There is no pending ownership transfer to this account.
+		NotPendingOwner,
+
+		/// The result of a numeric operation does not fit the entry's integer kind.
+		Overflow,
+
+		/// A clamp range whose lower bound is above its upper bound.
+		InvalidRange,
+
+		/// The entry's revision is not the one the caller expected; it changed since it was read.
+		StaleRevision,
+
+		/// The entry's value is not the one the caller expected.
+		ValueMismatch,
+
+		/// The value does not conform to the schema registered for its key.
+		SchemaMismatch,
+
+		/// The schema's bounds are inconsistent, or its default does not conform to it.
+		InvalidSchema,
+
+		/// The game's name is longer than `MaxNameLen`.
+		NameTooLong,
+
+		/// The game's description is longer than `MaxDescriptionLen`.
+		DescriptionTooLong,
+
+		/// The game's website is longer than `MaxWebsiteLen`.
+		WebsiteTooLong,
+
+		/// The game has more than `MaxGenres` genre tags.
+		TooManyGenres,
+
+		/// A genre tag is longer than `MaxGenreLen`.
+		GenreTooLong,
+
+		/// The batch has no operations or more than `MaxBatchOps` of them.
+		BadBatchSize,
+
+		/// The history depth is above `MaxHistoryDepth`.
+		HistoryTooDeep,
+
+		/// The entry's history holds no value of that revision.
+		RevisionNotInHistory,
+
+		/// The game's history is still being deleted. Set its depth to 0 until it is gone.
+		HistoryClearing,
+
+		/// The entry is locked, or write-once and already written. Locked and write-once entries
+		/// can't be set to expire either.
+		EntryLocked,
+
+		/// The expiry block is not after the current block.
+		ExpiryInPast,
+
+		/// `MaxExpiriesPerBlock` entries already expire at that block.
+		ExpiryQueueFull,
+
+		/// The game already has `MaxExpiries` entries set to expire.
+		TooManyExpiries,
+
+		/// The schedule starts in the past, ends before it starts, mixes blocks and timestamps, or
+		/// repeats before it ends.
+		InvalidSchedule,
+
+		/// The game already has `MaxSchedules` changes scheduled.
+		TooManySchedules,
+
+		/// No more changes can be scheduled at that block or timestamp.
+		ScheduleQueueFull,
+
+		/// The game already has `MaxLeaderboards` leaderboards.
+		TooManyLeaderboards,
+
+		/// The leaderboard ranks no players, or more than `MaxLeaderboardSize`.
+		InvalidLeaderboardSize,
+
+		/// More players were listed than the leaderboard ranks.
+		TooManyPlayers,
+
+		/// The game already has `MaxTriggers` trigger rules or `MaxGameTriggersPerKey` watching the
+		/// entry, or the entry is watched by `MaxTriggersPerKey` of them.
+		TooManyTriggers,
+
+		/// The clock doesn't move, or has more than `MaxClockPhases` phases, a phase name longer
+		/// than `MaxPhaseNameLen`, or phases out of order or outside the day.
+		InvalidClock,
+	}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub fps_game_authority: T::AccountId,
+		pub fps_game_id: T::GameID,
+		pub platformer_game_authority: T::AccountId,
+		pub platformer_game_id: T::GameID,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { 
+				fps_game_authority: Default::default(),
+				fps_game_id: Default::default(),
+				platformer_game_authority: Default::default(),
+				platformer_game_id: Default::default()
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+
+			// FPS
+			register::<T>(self.fps_game_id, &self.fps_game_authority, b"FPS".to_vec());
+
+			grant_access::<T>(self.fps_game_id, &self.fps_game_authority, Role::Owner);
+
+			let entry : DataEntry= (
+				String::into_bytes(String::from("Time")),
+				1i32.to_le_bytes().to_vec(),
+			);
+
+			let (key, value) = bounded_entry::<T>(entry).expect("genesis entries fit the configured bounds");
+			world_insert::<T>(self.fps_game_id, Route::External, &key, &value, &self.fps_game_authority, None)
+				.expect("genesis record fits the configured bounds");
+			
+
+			// PLATFORMER
+			register::<T>(self.platformer_game_id, &self.platformer_game_authority, b"Platformer".to_vec());
+
+			grant_access::<T>(self.platformer_game_id, &self.platformer_game_authority, Role::Owner);
+
+			let entry1 : DataEntry= (
+				String::into_bytes(String::from("Kills")),
+				0u32.to_le_bytes().to_vec(),
+			);
+			let entry2 : DataEntry= (
+				String::into_bytes(String::from("Deaths")),
+				0u32.to_le_bytes().to_vec(),
+			);
+
+			for entry in [entry1, entry2]
+			{
+				let (key, value) = bounded_entry::<T>(entry).expect("genesis entries fit the configured bounds");
+				world_insert::<T>(self.platformer_game_id, Route::External, &key, &value, &self.platformer_game_authority, None)
+					.expect("genesis record fits the configured bounds");
+			}
+		}
+	}
+
+	fn authority_role<T: Config>(who : &T::AccountId, game : T::GameID) -> Result<Role, Error<T>>
+	{
+		<Authorities<T>>::get(game, who).ok_or(Error::<T>::InvalidAuthority)
+	}
+
+	/// Make `who` an authority of `game`, keeping both directions of the index in step.
+	pub(super) fn grant_access<T: Config>(game : T::GameID, who : &T::AccountId, role : Role)
+	{
+		<Authorities<T>>::insert(game, who, role);
+		<AuthorityGames<T>>::insert(who, game, ());
+	}
+
+	/// Ensure `who` can still write `route` of `game`, for writes made on their behalf after the
+	/// call that set them up, such as trigger rules and scheduled changes.
+	fn ensure_can_write<T: Config>(who : &T::AccountId, game : T::GameID, route : Route) -> Result<(), Error<T>>
+	{
+		ensure_allowed::<T>(authority_role::<T>(who, game)?, route, Action::Write)
+	}
+
+	fn revoke_access<T: Config>(game : T::GameID, who : &T::AccountId)
+	{
+		<Authorities<T>>::remove(game, who);
+		<AuthorityGames<T>>::remove(who, game);
+	}
+
+	fn game_exists<T: Config>(game : &T::GameID) -> bool
+	{
+		<Games<T>>::contains_key(game)
+	}
+
+	/// Add a game to the registry, owned by `owner` and with only its name filled in.
+	fn register<T: Config>(game : T::GameID, owner : &T::AccountId, name : Vec<u8>)
+	{
+		let info = GameInfo::<T> {
+			owner: owner.clone(),
+			name: name.try_into().unwrap_or_default(),
+			description: Default::default(),
+			website: Default::default(),
+			icon: None,
+			genres: Default::default(),
+			created_at: <frame_system::Pallet<T>>::block_number(),
+		};
+
+		<Games<T>>::insert(game, info);
+	}
+
+	fn bounded_key<T: Config>(key : Skey) -> Result<BoundedKey<T>, Error<T>>
+	{
+		key.try_into().map_err(|_| Error::<T>::KeyTooLong)
+	}
+
+	fn bounded_value<T: Config>(value : Sval) -> Result<BoundedValue<T>, Error<T>>
+	{
+		value.try_into().map_err(|_| Error::<T>::ValueTooLarge)
+	}
+
+	fn bounded_entry<T: Config>(entry : DataEntry) -> Result<(BoundedKey<T>, BoundedValue<T>), Error<T>>
+	{
+		Ok((bounded_key::<T>(entry.0)?, bounded_value::<T>(entry.1)?))
+	}
+
+	/// Count one more entry against a record, failing once the route's limit is reached.
+	fn grow_record<T: Config>(len : &mut u32, route : Route) -> Result<(), Error<T>>
+	{
+		let max = match route {
+			Route::External => T::MaxExternalEntries::get(),
+			Route::Internal => T::MaxInternalEntries::get(),
+		};
+
+		ensure!(*len < max, Error::<T>::RecordFull);
+		*len += 1;
+
+		Ok(())
+	}
+
+	/// Count one less entry against a record, dropping the counter when it reaches zero.
+	fn shrink_record(len : &mut Option<u32>)
+	{
+		*len = len.and_then(|l| l.checked_sub(1)).filter(|l| *l > 0);
+	}
+
+	fn new_schema<T: Config>(kind : ValueKind, min : Option<i128>, max : Option<i128>, default : Option<Sval>) -> Result<ValueSchema<T>, sp_runtime::DispatchError>
+	{
+		let default = match default {
+			Some(value) => Some(bounded_value::<T>(value)?),
+			None => None,
+		};
+
+		let schema = ValueSchema::<T> { kind, min, max, default };
+		ensure!(schema.is_valid(), Error::<T>::InvalidSchema);
+
+		Ok(schema)
+	}
+
+	fn check_world_schema<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, value : &[u8]) -> DispatchResult
+	{
+		if let Some(schema) = <WorldSchemas<T>>::get((game, route, key))
+		{
+			ensure!(schema.accepts(value), Error::<T>::SchemaMismatch);
+		}
+
+		Ok(())
+	}
+
+	fn check_user_schema<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, value : &[u8]) -> DispatchResult
+	{
+		if let Some(schema) = <UserSchemas<T>>::get((game, route, key))
+		{
+			ensure!(schema.accepts(value), Error::<T>::SchemaMismatch);
+		}
+
+		Ok(())
+	}
+
+	/// Ensure an entry is still at `revision`, if the caller expected a revision.
+	fn check_revision<T: Config>(revision : u32, expected : Option<u32>) -> Result<(), Error<T>>
+	{
+		if let Some(expected) = expected
+		{
+			ensure!(expected == revision, Error::<T>::StaleRevision);
+		}
+
+		Ok(())
+	}
+
+	/// Ensure an entry's flags allow writing or removing it. `exists` is whether the entry has
+	/// been written already, which is all a write-once entry allows.
+	fn ensure_unlocked<T: Config>(flags : Option<EntryFlags>, exists : bool) -> Result<(), Error<T>>
+	{
+		if let Some(flags) = flags
+		{
+			ensure!(!flags.locked && !(flags.write_once && exists), Error::<T>::EntryLocked);
+		}
+
+		Ok(())
+	}
+
+	/// A value as reported in events, hashed if it is longer than `MaxEventValueLen`.
+	fn event_value<T: Config>(value : &[u8]) -> EventValue<T::Hash>
+	{
+		if value.len() > T::MaxEventValueLen::get() as usize
+		{
+			EventValue::Hashed(T::Hashing::hash(value))
+		}
+		else
+		{
+			EventValue::Raw(value.to_vec())
+		}
+	}
+
+	fn value_change<T: Config>(key : &BoundedKey<T>, old : Option<&BoundedValue<T>>, new : Option<&BoundedValue<T>>, revision : u32) -> Change<T>
+	{
+		ValueChange {
+			key: key.to_vec(),
+			old: old.map(|value| event_value::<T>(value)),
+			new: new.map(|value| event_value::<T>(value)),
+			revision,
+		}
+	}
+
+	/// Append a change to the game's change log, dropping the change that falls out of it.
+	fn log_change<T: Config>(game : T::GameID, route : Route, user : Option<&T::AccountId>, key : &BoundedKey<T>, op : ChangeOp)
+	{
+		let seq = <ChangeSeq<T>>::mutate(game, |seq| {
+			*seq = seq.saturating_add(1);
+			*seq
+		});
+
+		let record = ChangeRecord {
+			seq,
+			block: <frame_system::Pallet<T>>::block_number(),
+			route,
+			user: user.cloned(),
+			key: key.clone(),
+			op,
+		};
+
+		<ChangeLog<T>>::insert(game, seq, record);
+
+		if let Some(dropped) = seq.checked_sub(T::MaxChangeLogLen::get() as u64)
+		{
+			<ChangeLog<T>>::remove(game, dropped);
+		}
+	}
+
+	/// A freshly written entry, stamped with the writer and the current block and time.
+	fn stored_entry<T: Config>(value : &BoundedValue<T>, revision : u32, who : &T::AccountId) -> StoredEntry<T>
+	{
+		Entry {
+			value: value.clone(),
+			revision,
+			writer: Some(who.clone()),
+			block: <frame_system::Pallet<T>>::block_number(),
+			timestamp: <pallet_timestamp::Pallet<T>>::get(),
+		}
+	}
+
+	/// Append a newly written value to an entry's history, dropping the oldest values beyond `depth`.
+	fn push_history<T: Config>(history : &mut History<T>, depth : u32, value : &BoundedValue<T>, revision : u32, who : &T::AccountId)
+	{
+		let depth = depth.min(T::MaxHistoryDepth::get()) as usize;
+
+		while !history.is_empty() && history.len() >= depth
+		{
+			history.remove(0);
+		}
+
+		let entry = HistoryEntry {
+			value: value.clone(),
+			revision,
+			block: <frame_system::Pallet<T>>::block_number(),
+			writer: who.clone(),
+		};
+
+		// Cannot fail: the history is now shorter than `depth`, which is at most `MaxHistoryDepth`.
+		let _ = history.try_push(entry);
+	}
+
+	fn unbounded_history<T: Config>(history : History<T>) -> Vec<HistoryEntry<Sval, T::AccountId, T::BlockNumber>>
+	{
+		history.into_iter()
+			.map(|entry| HistoryEntry {
+				value: entry.value.into_inner(),
+				revision: entry.revision,
+				block: entry.block,
+				writer: entry.writer,
+			})
+			.collect()
+	}
+
+	fn unbounded_entry<T: Config>(entry : StoredEntry<T>) -> Entry<Sval, T::AccountId, T::BlockNumber, MomentOf<T>>
+	{
+		Entry {
+			value: entry.value.into_inner(),
+			revision: entry.revision,
+			writer: entry.writer,
+			block: entry.block,
+			timestamp: entry.timestamp,
+		}
+	}
+
+	/// Delete up to `MaxHistoryRemovals` values from the history of a game's world and user entries.
+	/// Returns whether the history is all gone.
+	fn clear_history<T: Config>(game : T::GameID) -> bool
+	{
+		let limit = T::MaxHistoryRemovals::get();
+
+		let removed = match <WorldHistory<T>>::remove_prefix((game,), Some(limit)) {
+			KillStorageResult::AllRemoved(removed) => removed,
+			KillStorageResult::SomeRemaining(_) => return false,
+		};
+
+		matches!(
+			<UserHistory<T>>::remove_prefix((game,), Some(limit.saturating_sub(removed))),
+			KillStorageResult::AllRemoved(_)
+		)
+	}
+
+	/// The value an entry held at `revision`, according to its history.
+	///
+	/// History recorded before removed entries kept their revision may hold a revision twice, in
+	/// which case the latest value wins.
+	fn historic_value<T: Config>(history : History<T>, revision : u32) -> Result<BoundedValue<T>, Error<T>>
+	{
+		history.into_iter()
+			.rev()
+			.find(|entry| entry.revision == revision)
+			.map(|entry| entry.value)
+			.ok_or(Error::<T>::RevisionNotInHistory)
+	}
+
+	/// Weight of writing a world entry, including its history and the change log, but not the trigger
+	/// rules watching it.
+	fn world_write_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads_writes(9, 7)
+	}
+
+	/// Weight of writing a world entry and firing the trigger rules watching it.
+	fn world_insert_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads(2)
+			.saturating_add(world_write_weight::<T>())
+			.saturating_add(max_trigger_weight::<T>())
+	}
+
+	/// Weight of removing a world entry, including its expiry and the change log.
+	fn world_remove_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads_writes(7, 9)
+	}
+
+	/// Weight of writing a user entry, including its history, the player indexes, the change log and
+	/// the leaderboard ranking by it.
+	fn user_write_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads_writes(11, 10)
+	}
+
+	/// Weight of removing a user entry, including its expiry, the player indexes, the change log and
+	/// the leaderboard ranking by it.
+	fn user_remove_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads_writes(9, 13)
+	}
+
+	/// Weight of moving an entry to another place in the expiry queue.
+	fn set_expiry_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads_writes(7, 6)
+	}
+
+	/// Weight of the heaviest batch step.
+	fn batch_op_weight<T: Config>() -> Weight
+	{
+		world_insert_weight::<T>()
+			.max(user_write_weight::<T>())
+			.max(world_remove_weight::<T>())
+			.max(user_remove_weight::<T>())
+			.saturating_add(set_expiry_weight::<T>())
+			.saturating_add(T::DbWeight::get().reads(2))
+	}
+
+	/// Insert or overwrite a world entry, and fire the trigger rules watching it.
+	fn world_insert<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, value : &BoundedValue<T>, who : &T::AccountId, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		let rules = match route {
+			Route::External => <Triggers<T>>::get(game, key),
+			Route::Internal => Default::default(),
+		};
+
+		if rules.is_empty()
+		{
+			return world_write::<T>(game, route, key, value, who, expected_revision);
+		}
+
+		let old = <WorldData<T>>::get((game, route, key)).map(|entry| entry.value);
+		let change = world_write::<T>(game, route, key, value, who, expected_revision)?;
+
+		fire_triggers::<T>(rules.into_inner(), old.as_ref().map(|old| &old[..]), value);
+
+		Ok(change)
+	}
+
+	/// Insert or overwrite a world entry, counting new keys against the record's limit.
+	///
+	/// Doesn't fire trigger rules, so the writes of trigger rules don't set off further rules.
+	fn world_write<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, value : &BoundedValue<T>, who : &T::AccountId, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		check_world_schema::<T>(game, route, key, value)?;
+
+		let current = <WorldData<T>>::get((game, route, key));
+		ensure_unlocked::<T>(<WorldEntryFlags<T>>::get((game, route, key)), current.is_some())?;
+
+		let revision = match &current {
+			Some(entry) => entry.revision,
+			None => <WorldRevisions<T>>::get((game, route, key)),
+		};
+		check_revision::<T>(revision, expected_revision)?;
+
+		if current.is_none()
+		{
+			<WorldRecordLen<T>>::try_mutate(game, route, |len| grow_record::<T>(len, route))?;
+			<WorldRevisions<T>>::remove((game, route, key));
+		}
+
+		let revision = revision.saturating_add(1);
+		<WorldData<T>>::insert((game, route, key), stored_entry::<T>(value, revision, who));
+
+		let depth = <HistoryDepth<T>>::get(game);
+		if depth > 0
+		{
+			<WorldHistory<T>>::mutate((game, route, key), |history| push_history::<T>(history, depth, value, revision, who));
+		}
+
+		log_change::<T>(game, route, None, key, ChangeOp::Write);
+
+		Ok(value_change::<T>(key, current.as_ref().map(|entry| &entry.value), Some(value), revision))
+	}
+
+	fn world_remove<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		let current = <WorldData<T>>::get((game, route, key)).ok_or(Error::<T>::NotFound)?;
+		ensure_unlocked::<T>(<WorldEntryFlags<T>>::get((game, route, key)), true)?;
+		check_revision::<T>(current.revision, expected_revision)?;
+
+		Ok(world_delete::<T>(game, route, key, current))
+	}
+
+	/// Remove a world entry regardless of its flags and revision, keeping its revision.
+	fn world_delete<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, current : StoredEntry<T>) -> Change<T>
+	{
+		<WorldData<T>>::remove((game, route, key));
+		<WorldRevisions<T>>::insert((game, route, key), current.revision);
+		world_clear_expiry::<T>(game, route, key);
+		<WorldRecordLen<T>>::mutate_exists(game, route, shrink_record);
+		log_change::<T>(game, route, None, key, ChangeOp::Remove);
+
+		value_change::<T>(key, Some(&current.value), None, current.revision)
+	}
+
+	/// Insert or overwrite a user entry, counting new keys against the record's limit.
+	fn user_insert<T: Config>(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>, value : &BoundedValue<T>, who : &T::AccountId, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		check_user_schema::<T>(game, route, key, value)?;
+
+		let current = <UserData<T>>::get((game, user, route, key));
+		ensure_unlocked::<T>(<UserEntryFlags<T>>::get((game, user, route, key)), current.is_some())?;
+
+		let revision = match &current {
+			Some(entry) => entry.revision,
+			None => <UserRevisions<T>>::get((game, user, route, key)),
+		};
+		check_revision::<T>(revision, expected_revision)?;
+
+		if current.is_none()
+		{
+			<UserRecordLen<T>>::try_mutate((game, user, route), |len| grow_record::<T>(len, route))?;
+			<UserRevisions<T>>::remove((game, user, route, key));
+			player_entry_added::<T>(game, user);
+		}
+
+		let revision = revision.saturating_add(1);
+		<UserData<T>>::insert((game, user, route, key), stored_entry::<T>(value, revision, who));
+
+		let depth = <HistoryDepth<T>>::get(game);
+		if depth > 0
+		{
+			<UserHistory<T>>::mutate((game, user, route, key), |history| push_history::<T>(history, depth, value, revision, who));
+		}
+
+		log_change::<T>(game, route, Some(user), key, ChangeOp::Write);
+		rank_player::<T>(game, user, route, key, Some(&value[..]));
+
+		Ok(value_change::<T>(key, current.as_ref().map(|entry| &entry.value), Some(value), revision))
+	}
+
+	fn user_remove<T: Config>(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		let current = <UserData<T>>::get((game, user, route, key)).ok_or(Error::<T>::NotFound)?;
+		ensure_unlocked::<T>(<UserEntryFlags<T>>::get((game, user, route, key)), true)?;
+		check_revision::<T>(current.revision, expected_revision)?;
+
+		Ok(user_delete::<T>(game, user, route, key, current))
+	}
+
+	/// Remove a user entry regardless of its flags and revision, keeping its revision.
+	fn user_delete<T: Config>(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>, current : StoredEntry<T>) -> Change<T>
+	{
+		<UserData<T>>::remove((game, user, route, key));
+		<UserRevisions<T>>::insert((game, user, route, key), current.revision);
+		user_clear_expiry::<T>(game, user, route, key);
+		<UserRecordLen<T>>::mutate_exists((game, user, route), shrink_record);
+		player_entry_removed::<T>(game, user);
+		log_change::<T>(game, route, Some(user), key, ChangeOp::Remove);
+		rank_player::<T>(game, user, route, key, None);
+
+		value_change::<T>(key, Some(&current.value), None, current.revision)
+	}
+
+	/// Update the leaderboard ranking players by a user key, if there is one, after `user`'s entry
+	/// changed to `value`.
+	fn rank_player<T: Config>(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>, value : Option<&[u8]>)
+	{
+		if let Some(mut board) = <Leaderboards<T>>::get((game, route, key))
+		{
+			if board.rank(user, value)
+			{
+				<Leaderboards<T>>::insert((game, route, key), board);
+			}
+		}
+	}
+
+	/// Queue `entry` for removal at block `at`.
+	fn schedule_expiry<T: Config>(at : T::BlockNumber, entry : ExpiringEntryOf<T>) -> Result<(), Error<T>>
+	{
+		ensure!(at > <frame_system::Pallet<T>>::block_number(), Error::<T>::ExpiryInPast);
+
+		<ExpiryCount<T>>::try_mutate(entry.game, |count| -> Result<(), Error<T>> {
+			ensure!(*count < T::MaxExpiries::get(), Error::<T>::TooManyExpiries);
+
+			<ExpiryQueue<T>>::try_mutate(at, |queue| queue.try_push(entry))
+				.map_err(|_| Error::<T>::ExpiryQueueFull)?;
+
+			*count += 1;
+			Ok(())
+		})
+	}
+
+	/// Take an entry out of the queue of the block it was due to expire at.
+	fn unqueue_expiry<T: Config>(at : T::BlockNumber, entry : &ExpiringEntryOf<T>)
+	{
+		<ExpiryQueue<T>>::mutate_exists(at, |queue| retain_queued(queue, |queued| queued != entry));
+		<ExpiryCount<T>>::mutate_exists(entry.game, shrink_record);
+	}
+
+	/// Clear a world entry's expiry, if it has one.
+	fn world_clear_expiry<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>)
+	{
+		if let Some(at) = <WorldExpiry<T>>::take((game, route, key))
+		{
+			unqueue_expiry::<T>(at, &ExpiringEntry { game, user: None, route, key: key.clone() });
+		}
+	}
+
+	/// Clear a user entry's expiry, if it has one.
+	fn user_clear_expiry<T: Config>(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>)
+	{
+		if let Some(at) = <UserExpiry<T>>::take((game, user, route, key))
+		{
+			unqueue_expiry::<T>(at, &ExpiringEntry { game, user: Some(user.clone()), route, key: key.clone() });
+		}
+	}
+
+	/// Set or clear the block a world entry expires at. Writing an entry without an expiry makes
+	/// it permanent again. Locked and write-once entries cannot expire.
+	fn world_set_expiry<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, expires_at : Option<T::BlockNumber>) -> DispatchResult
+	{
+		if <WorldExpiry<T>>::get((game, route, key)) == expires_at
+		{
+			return Ok(());
+		}
+
+		match expires_at {
+			Some(at) => {
+				ensure!(!<WorldEntryFlags<T>>::contains_key((game, route, key)), Error::<T>::EntryLocked);
+				world_clear_expiry::<T>(game, route, key);
+				schedule_expiry::<T>(at, ExpiringEntry { game, user: None, route, key: key.clone() })?;
+				<WorldExpiry<T>>::insert((game, route, key), at);
+			},
+			None => world_clear_expiry::<T>(game, route, key),
+		}
+
+		Ok(())
+	}
+
+	/// Set or clear the block a user entry expires at. See `world_set_expiry`.
+	fn user_set_expiry<T: Config>(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>, expires_at : Option<T::BlockNumber>) -> DispatchResult
+	{
+		if <UserExpiry<T>>::get((game, user, route, key)) == expires_at
+		{
+			return Ok(());
+		}
+
+		match expires_at {
+			Some(at) => {
+				ensure!(!<UserEntryFlags<T>>::contains_key((game, user, route, key)), Error::<T>::EntryLocked);
+				user_clear_expiry::<T>(game, user, route, key);
+				schedule_expiry::<T>(at, ExpiringEntry { game, user: Some(user.clone()), route, key: key.clone() })?;
+				<UserExpiry<T>>::insert((game, user, route, key), at);
+			},
+			None => user_clear_expiry::<T>(game, user, route, key),
+		}
+
+		Ok(())
+	}
+
+	/// Ensure a new schedule starts in the future, ends after it starts, and ends before it repeats.
+	fn check_schedule<T: Config>(start : ScheduleTimeOf<T>, end : Option<ScheduleTimeOf<T>>, period : Option<T::BlockNumber>) -> Result<(), Error<T>>
+	{
+		let valid = match (start, end) {
+			(ScheduleTime::Block(start), None) => start > <frame_system::Pallet<T>>::block_number(),
+			(ScheduleTime::Timestamp(start), None) => start > <pallet_timestamp::Pallet<T>>::get(),
+			(ScheduleTime::Block(start), Some(ScheduleTime::Block(end))) =>
+				start > <frame_system::Pallet<T>>::block_number()
+					&& end > start
+					&& period.map_or(true, |period| end - start < period),
+			(ScheduleTime::Timestamp(start), Some(ScheduleTime::Timestamp(end))) =>
+				start > <pallet_timestamp::Pallet<T>>::get() && end > start,
+			_ => false,
+		};
+
+		ensure!(valid && period != Some(Zero::zero()), Error::<T>::InvalidSchedule);
+
+		Ok(())
+	}
+
+	/// Queue a scheduled change to fire at `at`. Block-based changes can only be queued for later
+	/// blocks, as the queue of the current one may already have been read.
+	fn queue_schedule<T: Config>(game : T::GameID, id : u32, at : ScheduleTimeOf<T>) -> Result<(), Error<T>>
+	{
+		let queued = match at {
+			ScheduleTime::Block(block) => {
+				ensure!(block > <frame_system::Pallet<T>>::block_number(), Error::<T>::InvalidSchedule);
+				<BlockSchedules<T>>::try_mutate(block, |queue| queue.try_push((game, id)))
+			},
+			ScheduleTime::Timestamp(moment) => {
+				let slot = time_slot::<T>(moment);
+
+				<TimedSchedules<T>>::try_mutate(slot, |queue| queue.try_push((moment, game, id)))
+					.map(|()| <TimedScheduleCursor<T>>::mutate(|cursor| *cursor = Some(cursor.map_or(slot, |cursor| cursor.min(slot)))))
+			},
+		};
+
+		queued.map_err(|_| Error::<T>::ScheduleQueueFull)
+	}
+
+	/// Queue the next firing of a change that has already fired, pushing it back a block or a time
+	/// slot at a time while the queue it falls in is full, at most `MaxScheduleDelay` times.
+	fn requeue_schedule<T: Config>(game : T::GameID, id : u32, schedule : &mut ScheduledChange<T>) -> Result<(), Error<T>>
+	{
+		let mut delays = T::MaxScheduleDelay::get();
+
+		loop
+		{
+			match queue_schedule::<T>(game, id, schedule.next_time()) {
+				Err(Error::<T>::ScheduleQueueFull) if delays > 0 => {
+					delays -= 1;
+					delay_schedule::<T>(schedule);
+				},
+				result => return result,
+			}
+		}
+	}
+
+	/// Push the next firing of a change back by a block, or into the next time slot. A change that
+	/// isn't in effect yet keeps how long it is meant to last.
+	fn delay_schedule<T: Config>(schedule : &mut ScheduledChange<T>)
+	{
+		let slot = T::TimedScheduleSlot::get().max(One::one());
+		let delay = |time : ScheduleTimeOf<T>| match time {
+			ScheduleTime::Block(block) => ScheduleTime::Block(block.saturating_add(One::one())),
+			ScheduleTime::Timestamp(moment) => ScheduleTime::Timestamp(moment.saturating_add(slot)),
+		};
+
+		if schedule.active.is_none()
+		{
+			schedule.start = delay(schedule.start);
+		}
+
+		schedule.end = schedule.end.map(delay);
+	}
+
+	/// Remove a scheduled change from the queue it waits in to fire at `at`.
+	fn unqueue_schedule<T: Config>(game : T::GameID, id : u32, at : ScheduleTimeOf<T>)
+	{
+		match at {
+			ScheduleTime::Block(block) =>
+				<BlockSchedules<T>>::mutate_exists(block, |queue| retain_queued(queue, |queued| *queued != (game, id))),
+			ScheduleTime::Timestamp(moment) =>
+				<TimedSchedules<T>>::mutate_exists(time_slot::<T>(moment), |queue| retain_queued(queue, |queued| *queued != (moment, game, id))),
+		}
+	}
+
+	/// Keep the queued items `keep` accepts, dropping the queue once it is empty.
+	fn retain_queued<I, S>(queue : &mut Option<BoundedVec<I, S>>, keep : impl FnMut(&I) -> bool)
+	{
+		if let Some(items) = queue
+		{
+			items.retain(keep);
+
+			if items.is_empty()
+			{
+				*queue = None;
+			}
+		}
+	}
+
+	/// The time slot timestamp-based changes due at `moment` are queued in.
+	fn time_slot<T: Config>(moment : MomentOf<T>) -> MomentOf<T>
+	{
+		moment / T::TimedScheduleSlot::get().max(One::one())
+	}
+
+	/// Move a finished occurrence of a repeating change to its next one. `false` if the change
+	/// doesn't repeat.
+	///
+	/// The next occurrence starts `period` blocks after this one did, and lasts `length` blocks,
+	/// at least one. Repeats are always block-based, whichever way the first occurrence was set.
+	fn repeat_schedule<T: Config>(schedule : &mut ScheduledChange<T>, started : T::BlockNumber, length : T::BlockNumber) -> bool
+	{
+		let period = match schedule.period {
+			Some(period) => period,
+			None => return false,
+		};
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		let start = started.saturating_add(period).max(now.saturating_add(One::one()));
+
+		schedule.start = ScheduleTime::Block(start);
+		schedule.end = schedule.end.map(|_| ScheduleTime::Block(start.saturating_add(length.max(One::one()))));
+		schedule.active = None;
+
+		true
+	}
+
+	/// How many blocks an occurrence of a change is meant to last: the blocks between its start
+	/// and end if both are blocks, or one block otherwise.
+	fn planned_length<T: Config>(schedule : &ScheduledChange<T>) -> T::BlockNumber
+	{
+		match (schedule.start, schedule.end) {
+			(ScheduleTime::Block(start), Some(ScheduleTime::Block(end))) => end.saturating_sub(start),
+			_ => One::one(),
+		}
+	}
+
+	/// Weight of firing a single scheduled change, including pushing its next firing back as far
+	/// as `MaxScheduleDelay`.
+	fn schedule_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads_writes(6, 4)
+			.saturating_add(T::DbWeight::get().reads(T::MaxScheduleDelay::get() as Weight))
+			.saturating_add(world_insert_weight::<T>().max(world_remove_weight::<T>()))
+	}
+
+	/// Weight of the trigger rules a single world write can fire, at most `MaxTriggersPerKey`.
+	fn max_trigger_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads(1)
+			.saturating_add(world_write_weight::<T>())
+			.saturating_mul(T::MaxTriggersPerKey::get() as Weight)
+	}
+
+	/// Write the entries of the rules a watched entry crossed the threshold of, going from `old`
+	/// to `new`. Values that don't read as the rule's integer kind never cross it.
+	fn fire_triggers<T: Config>(rules : Vec<TriggerRule<T>>, old : Option<&[u8]>, new : &[u8])
+	{
+		for rule in rules
+		{
+			let new = match rule.kind.decode(new) {
+				Some(new) => new,
+				None => continue,
+			};
+
+			if !rule.threshold.crossed(old.and_then(|old| rule.kind.decode(old)), new)
+			{
+				continue;
+			}
+
+			let written = ensure_can_write::<T>(&rule.creator, rule.game, rule.route)
+				.map_err(sp_runtime::DispatchError::from)
+				.and_then(|()| world_write::<T>(rule.game, rule.route, &rule.key, &rule.value, &rule.creator, None));
+
+			let event = match written {
+				Ok(change) => Event::TriggerFired(rule.game, rule.id, rule.route, change),
+				Err(e) => Event::TriggerFailed(rule.game, rule.id, e),
+			};
+
+			<Pallet<T>>::deposit_event(event);
+		}
+	}
+
+	/// Apply or revert a scheduled change if it is still due at `at`, and queue its next firing.
+	fn fire_schedule<T: Config>(game : T::GameID, id : u32, at : ScheduleTimeOf<T>)
+	{
+		let mut schedule = match <Schedules<T>>::get(game, id) {
+			Some(schedule) if schedule.next_time() == at => schedule,
+			_ => return,
+		};
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		let route = schedule.route;
+		let key = schedule.key.clone();
+
+		let (result, keep) = match schedule.active.take() {
+			None => {
+				let previous = <WorldData<T>>::get((game, route, &key)).map(|entry| entry.value);
+				let result = ensure_can_write::<T>(&schedule.creator, game, route)
+					.map_err(sp_runtime::DispatchError::from)
+					.and_then(|()| world_insert::<T>(game, route, &key, &schedule.value, &schedule.creator, None))
+					.map(|change| Event::ScheduledChangeApplied(game, id, route, change));
+
+				// A change that failed to apply has nothing to revert, and moves on to its next
+				// occurrence as if it had lasted as planned.
+				let keep = if result.is_ok() && schedule.end.is_some()
+				{
+					schedule.active = Some(ActiveChange { started: now, previous });
+					true
+				}
+				else
+				{
+					let length = planned_length::<T>(&schedule);
+					repeat_schedule::<T>(&mut schedule, now, length)
+				};
+
+				(result, keep)
+			},
+			Some(active) => {
+				let result = match &active.previous {
+					Some(previous) => world_insert::<T>(game, route, &key, previous, &schedule.creator, None),
+					None => world_remove::<T>(game, route, &key, None),
+				}
+				.map(|change| Event::ScheduledChangeReverted(game, id, route, change));
+
+				(result, repeat_schedule::<T>(&mut schedule, active.started, now - active.started))
+			},
+		};
+
+		<Pallet<T>>::deposit_event(result.unwrap_or_else(|e| Event::ScheduledChangeFailed(game, id, e)));
+
+		if keep
+		{
+			match requeue_schedule::<T>(game, id, &mut schedule) {
+				Ok(()) => {
+					<Schedules<T>>::insert(game, id, schedule);
+					return;
+				},
+				Err(e) => <Pallet<T>>::deposit_event(Event::ScheduledChangeFailed(game, id, e.into())),
+			}
+		}
+
+		<Schedules<T>>::remove(game, id);
+		<ScheduleCount<T>>::mutate_exists(game, shrink_record);
+	}
+
+	/// Fire the scheduled changes due at block `now`, and the timestamp-based changes due by the
+	/// parent block's timestamp, at most `MaxSchedulesPerBlock` of them.
+	///
+	/// Time slots are checked from the cursor on, at most `MaxTimedSlotsPerBlock` of them. The
+	/// cursor stays at the first slot with changes left over, whether they aren't due yet or
+	/// didn't fit in the block.
+	fn run_schedules<T: Config>(now : T::BlockNumber) -> Weight
+	{
+		let mut weight = T::DbWeight::get().reads_writes(3, 2);
+
+		for (game, id) in <BlockSchedules<T>>::take(now).into_inner()
+		{
+			weight = weight.saturating_add(schedule_weight::<T>());
+			fire_schedule::<T>(game, id, ScheduleTime::Block(now));
+		}
+
+		let time = <pallet_timestamp::Pallet<T>>::get();
+		let last = time_slot::<T>(time);
+		let mut slot = match <TimedScheduleCursor<T>>::get() {
+			Some(slot) => slot,
+			None => return weight,
+		};
+		let mut slots = T::MaxTimedSlotsPerBlock::get();
+		let mut fired = T::MaxSchedulesPerBlock::get();
+
+		while slot <= last && slots > 0
+		{
+			slots -= 1;
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 1));
+
+			let due : Vec<_> = <TimedSchedules<T>>::get(slot).into_inner().into_iter()
+				.filter(|(moment, _, _)| *moment <= time)
+				.take(fired as usize)
+				.collect();
+
+			for &(moment, game, id) in &due
+			{
+				fired -= 1;
+				weight = weight.saturating_add(schedule_weight::<T>());
+				fire_schedule::<T>(game, id, ScheduleTime::Timestamp(moment));
+			}
+
+			// Re-read the queue, as the changes fired may have queued their next firing in it.
+			<TimedSchedules<T>>::mutate_exists(slot, |queue| retain_queued(queue, |queued| !due.contains(queued)));
+
+			if <TimedSchedules<T>>::contains_key(slot)
+			{
+				break;
+			}
+
+			slot += One::one();
+		}
+
+		<TimedScheduleCursor<T>>::put(slot);
+
+		weight
+	}
+
+	/// Weight of removing a single expired entry.
+	fn expiry_weight<T: Config>() -> Weight
+	{
+		T::DbWeight::get().reads(1).saturating_add(user_remove_weight::<T>().max(world_remove_weight::<T>()))
+	}
+
+	/// Remove a queued entry if it is still due to expire at `block`.
+	///
+	/// An entry locked or made write-once since its expiry was set is kept, and its expiry dropped.
+	fn expire_entry<T: Config>(block : T::BlockNumber, entry : ExpiringEntryOf<T>)
+	{
+		let ExpiringEntry { game, user, route, key } = entry;
+
+		let change = match &user {
+			None => {
+				if <WorldExpiry<T>>::get((game, route, &key)) != Some(block)
+				{
+					return;
+				}
+
+				if <WorldEntryFlags<T>>::contains_key((game, route, &key))
+				{
+					world_clear_expiry::<T>(game, route, &key);
+					return;
+				}
+
+				match <WorldData<T>>::get((game, route, &key)) {
+					Some(current) => world_delete::<T>(game, route, &key, current),
+					None => return,
+				}
+			},
+			Some(user) => {
+				if <UserExpiry<T>>::get((game, user, route, &key)) != Some(block)
+				{
+					return;
+				}
+
+				if <UserEntryFlags<T>>::contains_key((game, user, route, &key))
+				{
+					user_clear_expiry::<T>(game, user, route, &key);
+					return;
+				}
+
+				match <UserData<T>>::get((game, user, route, &key)) {
+					Some(current) => user_delete::<T>(game, user, route, &key, current),
+					None => return,
+				}
+			},
+		};
+
+		<Pallet<T>>::deposit_event(Event::EntryExpired(game, user, route, change));
+	}
+
+	/// Remove the entries due to expire up to block `now`, within `ExpiryWeightBudget`.
+	///
+	/// Entries left over when the budget runs out stay queued, and the cursor stays at their block
+	/// until they are removed.
+	fn expire_entries<T: Config>(now : T::BlockNumber) -> Weight
+	{
+		let budget = T::ExpiryWeightBudget::get();
+		let mut weight = T::DbWeight::get().reads_writes(1, 1);
+		let mut block = <ExpiryCursor<T>>::get().unwrap_or(now);
+
+		while block <= now
+		{
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+			let mut queue = <ExpiryQueue<T>>::take(block).into_inner().into_iter();
+
+			while let Some(entry) = queue.next()
+			{
+				if weight.saturating_add(expiry_weight::<T>()) > budget
+				{
+					let rest : Vec<_> = sp_std::iter::once(entry).chain(queue).collect();
+
+					// Cannot fail: the rest of the queue is no longer than the queue.
+					<ExpiryQueue<T>>::insert(block, BoundedVec::try_from(rest).unwrap_or_default());
+					<ExpiryCursor<T>>::put(block);
+
+					return weight;
+				}
+
+				weight = weight.saturating_add(expiry_weight::<T>());
+				expire_entry::<T>(block, entry);
+			}
+
+			block += One::one();
+		}
+
+		<ExpiryCursor<T>>::put(block);
+
+		weight
+	}
+
+	/// Ensure a stored value is `expected`, where `None` stands for an absent entry.
+	fn check_value<T: Config>(current : Option<&StoredEntry<T>>, expected : Option<&[u8]>) -> Result<(), Error<T>>
+	{
+		ensure!(current.map(|entry| &entry.value[..]) == expected, Error::<T>::ValueMismatch);
+
+		Ok(())
+	}
+
+	/// Apply a numeric operation to `current`, mapping failures to pallet errors.
+	fn numeric_result<T: Config>(kind : IntKind, op : NumericOp, overflow : OverflowPolicy, current : &[u8], operand : &[u8]) -> Result<BoundedValue<T>, sp_runtime::DispatchError>
+	{
+		let result = numeric::apply(kind, op, overflow, current, operand).map_err(|e| match e {
+			NumericError::BadSize => Error::<T>::BadSize,
+			NumericError::Overflow => Error::<T>::Overflow,
+			NumericError::InvalidRange => Error::<T>::InvalidRange,
+		})?;
+
+		Ok(bounded_value::<T>(result)?)
+	}
+
+	/// Apply a numeric operation to a world entry, starting from the key's schema default while the
+	/// entry is absent.
+	fn world_numeric<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, kind : IntKind, op : NumericOp, operand : &[u8], overflow : OverflowPolicy, who : &T::AccountId, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		let current = <WorldData<T>>::get((game, route, key))
+			.map(|entry| entry.value)
+			.or_else(|| <WorldSchemas<T>>::get((game, route, key)).and_then(|schema| schema.default))
+			.ok_or(Error::<T>::NotFound)?;
+
+		let result = numeric_result::<T>(kind, op, overflow, &current, operand)?;
+
+		world_insert::<T>(game, route, key, &result, who, expected_revision)
+	}
+
+	/// Apply a numeric operation to a user entry. See `world_numeric`.
+	fn user_numeric<T: Config>(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>, kind : IntKind, op : NumericOp, operand : &[u8], overflow : OverflowPolicy, who : &T::AccountId, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		let current = <UserData<T>>::get((game, user, route, key))
+			.map(|entry| entry.value)
+			.or_else(|| <UserSchemas<T>>::get((game, route, key)).and_then(|schema| schema.default))
+			.ok_or(Error::<T>::NotFound)?;
+
+		let result = numeric_result::<T>(kind, op, overflow, &current, operand)?;
+
+		user_insert::<T>(game, user, route, key, &result, who, expected_revision)
+	}
+
+	/// Apply a bit operation to `current`, failing before anything is allocated if the result would
+	/// not fit `MaxValueLen`.
+	fn bits_result<T: Config>(op : &BitOp, current : Option<BoundedValue<T>>) -> Result<BoundedValue<T>, Error<T>>
+	{
+		frame_support::ensure!(op.required_len() <= T::MaxValueLen::get() as usize, Error::<T>::ValueTooLarge);
+
+		let mut value = current.map(|v| v.into_inner()).unwrap_or_default();
+		op.apply(&mut value);
+
+		bounded_value::<T>(value)
+	}
+
+	/// Apply a bit operation to a world entry. An absent entry starts from its schema default, or
+	/// with every bit clear.
+	fn world_bits<T: Config>(game : T::GameID, route : Route, key : &BoundedKey<T>, op : &BitOp, who : &T::AccountId, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		let current = <WorldData<T>>::get((game, route, key))
+			.map(|entry| entry.value)
+			.or_else(|| <WorldSchemas<T>>::get((game, route, key)).and_then(|schema| schema.default));
+
+		let result = bits_result::<T>(op, current)?;
+
+		world_insert::<T>(game, route, key, &result, who, expected_revision)
+	}
+
+	/// Apply a bit operation to a user entry. See `world_bits`.
+	fn user_bits<T: Config>(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>, op : &BitOp, who : &T::AccountId, expected_revision : Option<u32>) -> Result<Change<T>, sp_runtime::DispatchError>
+	{
+		let current = <UserData<T>>::get((game, user, route, key))
+			.map(|entry| entry.value)
+			.or_else(|| <UserSchemas<T>>::get((game, route, key)).and_then(|schema| schema.default));
+
+		let result = bits_result::<T>(op, current)?;
+
+		user_insert::<T>(game, user, route, key, &result, who, expected_revision)
+	}
+
+	/// Track a newly created user entry in the player indexes.
+	pub(super) fn player_entry_added<T: Config>(game : T::GameID, user : &T::AccountId)
+	{
+		<GamePlayers<T>>::mutate(game, user, |count| *count = count.saturating_add(1));
+		<PlayerGames<T>>::insert(user, game, ());
+	}
+
+	/// Untrack a removed user entry, dropping the player from the indexes with their last entry.
+	pub(super) fn player_entry_removed<T: Config>(game : T::GameID, user : &T::AccountId)
+	{
+		let remaining = <GamePlayers<T>>::mutate(game, user, |count| {
+			*count = count.saturating_sub(1);
+			*count
+		});
+
+		if remaining == 0
+		{
+			<GamePlayers<T>>::remove(game, user);
+			<PlayerGames<T>>::remove(user, game);
+		}
+	}
+
+	fn is_authorized_call<T: Config> (origin: OriginFor<T>, game : T::GameID, route : Route, action : Action) -> Result<T::AccountId, sp_runtime::DispatchError>
+	{
+		let (who, role) = is_authority_call::<T>(origin, game)?;
+
+		ensure_allowed::<T>(role, route, action)?;
+
+		Ok(who)
+	}
+
+	/// Ensure the caller holds any role in `game`.
+	fn is_authority_call<T: Config> (origin: OriginFor<T>, game : T::GameID) -> Result<(T::AccountId, Role), sp_runtime::DispatchError>
+	{
+		let who = ensure_signed(origin)?;
+
+		let role = authority_role::<T>(&who, game)?;
+
+		Ok((who, role))
+	}
+
+	fn ensure_allowed<T: Config>(role : Role, route : Route, action : Action) -> Result<(), Error<T>>
+	{
+		let allowed = match action {
+			Action::Write => role.can_write(route),
+			Action::Remove => role.can_remove(route),
+		};
+
+		ensure!(allowed, Error::<T>::InvalidAccess);
+
+		Ok(())
+	}
+
+	/// Apply a single batch step and emit its event. Authorization is checked for the whole batch
+	/// beforehand.
+	fn apply_batch_op<T: Config>(game : T::GameID, who : &T::AccountId, op : BatchOp<T::AccountId, T::BlockNumber>) -> DispatchResult
+	{
+		let event = match op {
+			BatchOp::WorldUpdate { route, entry, expected_revision, expires_at } => {
+				let (key, value) = bounded_entry::<T>(entry)?;
+				let change = world_insert::<T>(game, route, &key, &value, who, expected_revision)?;
+				world_set_expiry::<T>(game, route, &key, expires_at)?;
+				Event::WorldDataUpdated(game, route, who.clone(), change)
+			},
+			BatchOp::WorldRemove { route, key, expected_revision } => {
+				let change = world_remove::<T>(game, route, &bounded_key::<T>(key)?, expected_revision)?;
+				Event::WorldDataRemoved(game, route, who.clone(), change)
+			},
+			BatchOp::WorldMod { route, key, kind, op, operand, overflow, expected_revision } => {
+				let change = world_numeric::<T>(game, route, &bounded_key::<T>(key)?, kind, op, &operand, overflow, who, expected_revision)?;
+				Event::WorldDataModified(game, route, who.clone(), change)
+			},
+			BatchOp::UserUpdate { user, route, entry, expected_revision, expires_at } => {
+				let (key, value) = bounded_entry::<T>(entry)?;
+				let change = user_insert::<T>(game, &user, route, &key, &value, who, expected_revision)?;
+				user_set_expiry::<T>(game, &user, route, &key, expires_at)?;
+				Event::UserDataUpdated(game, user, route, who.clone(), change)
+			},
+			BatchOp::UserRemove { user, route, key, expected_revision } => {
+				let change = user_remove::<T>(game, &user, route, &bounded_key::<T>(key)?, expected_revision)?;
+				Event::UserDataRemoved(game, user, route, who.clone(), change)
+			},
+			BatchOp::UserMod { user, route, key, kind, op, operand, overflow, expected_revision } => {
+				let change = user_numeric::<T>(game, &user, route, &bounded_key::<T>(key)?, kind, op, &operand, overflow, who, expected_revision)?;
+				Event::UserDataModified(game, user, route, who.clone(), change)
+			},
+		};
+
+		<Pallet<T>>::deposit_event(event);
+
+		Ok(())
+	}
+
+	/// The flags to store in place of `current`, refusing to clear a write-once flag. `None`
+	/// when no flag is left set.
+	fn updated_flags<T: Config>(current : Option<EntryFlags>, flags : EntryFlags) -> Result<Option<EntryFlags>, Error<T>>
+	{
+		ensure!(flags.write_once || !current.map_or(false, |current| current.write_once), Error::<T>::EntryLocked);
+
+		Ok(if flags == EntryFlags::default() { None } else { Some(flags) })
+	}
+
+	/// Ensure the caller is the owner or an admin of `game`.
+	fn is_manager_call<T: Config> (origin: OriginFor<T>, game : T::GameID) -> Result<(T::AccountId, Role), sp_runtime::DispatchError>
+	{
+		let (who, role) = is_authority_call::<T>(origin, game)?;
+
+		ensure!(role.is_manager(), Error::<T>::InvalidAccess);
+
+		Ok((who, role))
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now : T::BlockNumber) -> Weight {
+			expire_entries::<T>(now).saturating_add(run_schedules::<T>(now))
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::migrate::<T>()
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Deposit an event indexed by its topics, so clients can subscribe to a single game,
+		/// route or key instead of decoding every event of every block.
+		pub(super) fn deposit_event(event : Event<T>)
+		{
+			let topics = event.topics();
+			<frame_system::Pallet<T>>::deposit_event_indexed(&topics, <T as Config>::Event::from(event).into());
+		}
+
+		/// The topic of every event about `game`.
+		pub fn game_topic(game : T::GameID) -> T::Hash
+		{
+			T::Hashing::hash_of(&(b"metasave:game", game))
+		}
+
+		/// The topic of every event about an entry or schema of `game` under `route`.
+		pub fn route_topic(game : T::GameID, route : Route) -> T::Hash
+		{
+			T::Hashing::hash_of(&(b"metasave:route", game, route))
+		}
+
+		/// The topic of every event about `key` in `game`, on either route and for any player.
+		pub fn key_topic(game : T::GameID, key : &[u8]) -> T::Hash
+		{
+			T::Hashing::hash_of(&(b"metasave:key", game, key))
+		}
+
+		/// Collect every entry of a game world's route into a single record.
+		///
+		/// Prefer reading `WorldData` directly when only a few keys are needed.
+		pub fn world_data_record(game : T::GameID, route : Route) -> DataRecord
+		{
+			<WorldData<T>>::iter_prefix((game, route))
+				.map(|(key, entry)| (key.into_inner(), entry.value.into_inner()))
+				.collect()
+		}
+
+		/// Collect every entry a player holds under a game's route.
+		pub fn user_data_record(game : T::GameID, user : &T::AccountId, route : Route) -> DataRecord
+		{
+			<UserData<T>>::iter_prefix((game, user.clone(), route))
+				.map(|(key, entry)| (key.into_inner(), entry.value.into_inner()))
+				.collect()
+		}
+
+		/// Every player holding data in `game`.
+		pub fn players_of(game : T::GameID) -> impl Iterator<Item = T::AccountId>
+		{
+			<GamePlayers<T>>::iter_key_prefix(game)
+		}
+
+		/// Every game `user` holds data in.
+		pub fn games_of(user : &T::AccountId) -> impl Iterator<Item = T::GameID>
+		{
+			<PlayerGames<T>>::iter_key_prefix(user)
+		}
+
+		/// Every authority of `game`, with the role they hold.
+		pub fn authorities_of(game : T::GameID) -> impl Iterator<Item = (T::AccountId, Role)>
+		{
+			<Authorities<T>>::iter_prefix(game)
+		}
+
+		/// Every game `who` is an authority of.
+		pub fn authority_games(who : &T::AccountId) -> impl Iterator<Item = T::GameID>
+		{
+			<AuthorityGames<T>>::iter_key_prefix(who)
+		}
+
+		/// The role `who` holds in `game`, if they are one of its authorities.
+		pub fn role_of(game : T::GameID, who : &T::AccountId) -> Option<Role>
+		{
+			<Authorities<T>>::get(game, who)
+		}
+
+		/// The schema values of a world key must conform to, if one is registered.
+		pub fn world_schema(game : T::GameID, route : Route, key : &BoundedKey<T>) -> Option<ValueSchema<T>>
+		{
+			<WorldSchemas<T>>::get((game, route, key))
+		}
+
+		/// The schema values of a user key must conform to, if one is registered.
+		pub fn user_schema(game : T::GameID, route : Route, key : &BoundedKey<T>) -> Option<ValueSchema<T>>
+		{
+			<UserSchemas<T>>::get((game, route, key))
+		}
+
+		/// Every change of `game` after the change numbered `seq`, oldest first.
+		///
+		/// Only the last `MaxChangeLogLen` changes are kept. If the first change returned is not
+		/// `seq + 1`, the caller missed changes and has to read the game's records in full.
+		pub fn changes_since(game : T::GameID, seq : u64) -> Vec<ChangeRecord<Skey, T::AccountId, T::BlockNumber>>
+		{
+			let latest = <ChangeSeq<T>>::get(game);
+			let oldest = latest.saturating_sub(T::MaxChangeLogLen::get() as u64).saturating_add(1);
+
+			(seq.saturating_add(1).max(oldest)..=latest)
+				.filter_map(|seq| <ChangeLog<T>>::get(game, seq))
+				.map(|record| ChangeRecord {
+					seq: record.seq,
+					block: record.block,
+					route: record.route,
+					user: record.user,
+					key: record.key.into_inner(),
+					op: record.op,
+				})
+				.collect()
+		}
+
+		/// The recorded values of a world entry, oldest first.
+		pub fn world_history(game : T::GameID, route : Route, key : Skey) -> Vec<HistoryEntry<Sval, T::AccountId, T::BlockNumber>>
+		{
+			match bounded_key::<T>(key) {
+				Ok(key) => unbounded_history::<T>(<WorldHistory<T>>::get((game, route, key))),
+				Err(_) => Vec::new(),
+			}
+		}
+
+		/// The recorded values of a user entry, oldest first.
+		pub fn user_history(game : T::GameID, user : &T::AccountId, route : Route, key : Skey) -> Vec<HistoryEntry<Sval, T::AccountId, T::BlockNumber>>
+		{
+			match bounded_key::<T>(key) {
+				Ok(key) => unbounded_history::<T>(<UserHistory<T>>::get((game, user, route, key))),
+				Err(_) => Vec::new(),
+			}
+		}
+
+		/// The players ranked by a user key of `game` and their scores, best first, or `None` if
+		/// the key has no leaderboard.
+		pub fn leaderboard(game : T::GameID, route : Route, key : Skey) -> Option<Vec<(T::AccountId, Number)>>
+		{
+			let key = bounded_key::<T>(key).ok()?;
+			<Leaderboards<T>>::get((game, route, key)).map(|board| board.ranking.into_inner())
+		}
+
+		/// The trigger rules watching an external world entry of `game`.
+		pub fn triggers_on(game : T::GameID, key : &BoundedKey<T>) -> Vec<TriggerRule<T>>
+		{
+			<Triggers<T>>::get(game, key).into_inner()
+		}
+
+		/// The current in-world time and phase of `game`, if it has a clock.
+		///
+		/// Read from the timestamp of the latest block, so it only moves from block to block.
+		pub fn world_time(game : T::GameID) -> Option<WorldTime<Vec<u8>>>
+		{
+			let clock = <WorldClocks<T>>::get(game)?;
+			let time = clock.read::<PhaseName<T>>(<pallet_timestamp::Pallet<T>>::get());
+
+			Some(WorldTime {
+				elapsed: time.elapsed,
+				day: time.day,
+				time_of_day: time.time_of_day,
+				phase: time.phase.map(|name| name.into_inner()),
+			})
+		}
+
+		/// Every change scheduled in `game`, by id.
+		pub fn scheduled_changes(game : T::GameID) -> impl Iterator<Item = (u32, ScheduledChange<T>)>
+		{
+			<Schedules<T>>::iter_prefix(game)
+		}
+
+		/// The write restrictions on a world entry.
+		pub fn world_entry_flags(game : T::GameID, route : Route, key : &BoundedKey<T>) -> EntryFlags
+		{
+			<WorldEntryFlags<T>>::get((game, route, key)).unwrap_or_default()
+		}
+
+		/// The write restrictions on a user entry.
+		pub fn user_entry_flags(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>) -> EntryFlags
+		{
+			<UserEntryFlags<T>>::get((game, user, route, key)).unwrap_or_default()
+		}
+
+		/// A world entry with its revision, last writer and last-modified block and time.
+		pub fn world_entry(game : T::GameID, route : Route, key : &BoundedKey<T>) -> Option<StoredEntry<T>>
+		{
+			<WorldData<T>>::get((game, route, key))
+		}
+
+		/// A user entry with its revision, last writer and last-modified block and time.
+		pub fn user_entry(game : T::GameID, user : &T::AccountId, route : Route, key : &BoundedKey<T>) -> Option<StoredEntry<T>>
+		{
+			<UserData<T>>::get((game, user, route, key))
+		}
+
+		/// A world entry with its audit fields, for the runtime API.
+		pub fn world_entry_info(game : T::GameID, route : Route, key : Skey) -> Option<Entry<Sval, T::AccountId, T::BlockNumber, MomentOf<T>>>
+		{
+			let key = bounded_key::<T>(key).ok()?;
+			<WorldData<T>>::get((game, route, key)).map(unbounded_entry::<T>)
+		}
+
+		/// A user entry with its audit fields, for the runtime API.
+		pub fn user_entry_info(game : T::GameID, user : &T::AccountId, route : Route, key : Skey) -> Option<Entry<Sval, T::AccountId, T::BlockNumber, MomentOf<T>>>
+		{
+			let key = bounded_key::<T>(key).ok()?;
+			<UserData<T>>::get((game, user, route, key)).map(unbounded_entry::<T>)
+		}
+
+		/// Whether `bit` is set in a world entry, or `None` if there is no such entry.
+		pub fn world_test_bit(game : T::GameID, route : Route, key : Skey, bit : u32) -> Option<bool>
+		{
+			let key = bounded_key::<T>(key).ok()?;
+			<WorldData<T>>::get((game, route, key)).map(|entry| bits::test(&entry.value, bit))
+		}
+
+		/// Whether `bit` is set in a user entry, or `None` if there is no such entry.
+		pub fn user_test_bit(game : T::GameID, user : &T::AccountId, route : Route, key : Skey, bit : u32) -> Option<bool>
+		{
+			let key = bounded_key::<T>(key).ok()?;
+			<UserData<T>>::get((game, user, route, key)).map(|entry| bits::test(&entry.value, bit))
+		}
+
+		/// Remove everything a player holds under a game, on both routes, keeping the revisions of
+		/// the entries removed.
+		///
+		/// Returns the number of entries removed.
+		pub fn clear_player_data(game : T::GameID, user : &T::AccountId) -> u32
+		{
+			let removed = <GamePlayers<T>>::take(game, user);
+			<PlayerGames<T>>::remove(user, game);
+
+			for ((route, key), entry) in <UserData<T>>::drain_prefix((game, user.clone()))
+			{
+				<UserRevisions<T>>::insert((game, user, route, key), entry.revision);
+			}
+
+			<UserHistory<T>>::remove_prefix((game, user.clone()), None);
+			<UserEntryFlags<T>>::remove_prefix((game, user.clone()), None);
+
+			for ((route, key), at) in <UserExpiry<T>>::drain_prefix((game, user.clone()))
+			{
+				unqueue_expiry::<T>(at, &ExpiringEntry { game, user: Some(user.clone()), route, key });
+			}
+
+			<UserRecordLen<T>>::remove((game, user, Route::External));
+			<UserRecordLen<T>>::remove((game, user, Route::Internal));
+
+			let boards : Vec<_> = <Leaderboards<T>>::iter_prefix((game,)).collect();
+			for ((route, key), mut board) in boards
+			{
+				if board.rank(user, None)
+				{
+					<Leaderboards<T>>::insert((game, route, key), board);
+				}
+			}
+
+			removed
+		}
+	}
+
+	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
+	// These functions materialize as "extrinsics", which are often compared to transactions.
+	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+
+		/// Remove a world entry.
+		///
+		/// With `expected_revision`, fails with `StaleRevision` unless the entry is still at that
+		/// revision. The same applies to every update, remove and mod call.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(1).saturating_add(world_remove_weight::<T>()))]
+		pub fn world_remove_data_record(origin: OriginFor<T>, game : T::GameID, entry : DataEntry, route : Route, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Remove)?;
+			
+			let key = bounded_key::<T>(entry.0)?;
+
+			let change = world_remove::<T>(game, route, &key, expected_revision)?;
+
+			Self::deposit_event(Event::WorldDataRemoved(game, route, who, change));
+
+			Ok(())
+		}
+
+		/// Create or overwrite a world entry. An `expected_revision` of 0 only creates it.
+		///
+		/// With `expires_at`, the entry is removed at the start of that block unless it is updated
+		/// again before. Without it the entry is kept until removed, even if it was set to expire.
+		/// Other writes such as mods keep the entry's expiry. Locked and write-once entries can't
+		/// be set to expire, and an entry flagged after it was set to expire is kept.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(1).saturating_add(world_insert_weight::<T>()).saturating_add(set_expiry_weight::<T>()))]
+		#[transactional]
+		pub fn world_update_data_record(origin: OriginFor<T>, game : T::GameID, entry : DataEntry, route : Route, expected_revision : Option<u32>, expires_at : Option<T::BlockNumber>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let (key, value) = bounded_entry::<T>(entry)?;
+
+			// Generate a new entry, or assign new value to existing entry.
+			let change = world_insert::<T>(game, route, &key, &value, &who, expected_revision)?;
+			world_set_expiry::<T>(game, route, &key, expires_at)?;
+
+			// Emit an event.
+			Self::deposit_event(Event::WorldDataUpdated(game, route, who, change));
+			
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(3).saturating_add(world_insert_weight::<T>()))]
+		pub fn world_mod_data_record(origin: OriginFor<T>, game : T::GameID, new_entry : DataEntry, route : Route, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let key = bounded_key::<T>(new_entry.0)?;
+
+			// Increment|Decrement value.
+			let change = world_numeric::<T>(game, route, &key, IntKind::I32, NumericOp::Add, &new_entry.1, OverflowPolicy::Checked, &who, expected_revision)?;
+
+			// Emit an event.
+			Self::deposit_event(Event::WorldDataModified(game, route, who, change));
+			
+			Ok(())
+		}
+
+		/// Apply an arithmetic operation to an integer world entry of any width.
+		///
+		/// `operand` is a little-endian integer of the same `kind` as the entry, or two of them for
+		/// `Clamp`. An absent entry starts from its schema's default, if it has one. Comparisons such
+		/// as `SetIfGreater` are evaluated against the stored value in the same call, so concurrent
+		/// writers can't replace a better value with a worse one.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(3).saturating_add(world_insert_weight::<T>()))]
+		pub fn world_numeric_op(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey, kind : IntKind, op : NumericOp, operand : Sval, overflow : OverflowPolicy, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let key = bounded_key::<T>(key)?;
+
+			let change = world_numeric::<T>(game, route, &key, kind, op, &operand, overflow, &who, expected_revision)?;
+
+			Self::deposit_event(Event::WorldDataModified(game, route, who, change));
+
+			Ok(())
+		}
+
+		/// Write a world entry only if its current value is `expected`, or if it is absent when
+		/// `expected` is `None`. Fails with `ValueMismatch` otherwise, so e.g. only the first
+		/// player to claim an item gets it.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(2).saturating_add(world_insert_weight::<T>()))]
+		pub fn world_compare_and_swap(origin: OriginFor<T>, game : T::GameID, route : Route, entry : DataEntry, expected : Option<Sval>, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let (key, value) = bounded_entry::<T>(entry)?;
+
+			check_value::<T>(<WorldData<T>>::get((game, route, &key)).as_ref(), expected.as_deref())?;
+
+			let change = world_insert::<T>(game, route, &key, &value, &who, expected_revision)?;
+
+			Self::deposit_event(Event::WorldDataUpdated(game, route, who, change));
+
+			Ok(())
+		}
+
+		/// Set, clear or toggle bits of a world entry in place, so writers unlocking different
+		/// achievements don't overwrite each other.
+		///
+		/// The value grows with zero bytes as needed. See the `bits` module for the bit order.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(3).saturating_add(world_insert_weight::<T>()))]
+		pub fn world_bit_op(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey, op : BitOp, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let key = bounded_key::<T>(key)?;
+
+			let change = world_bits::<T>(game, route, &key, &op, &who, expected_revision)?;
+
+			Self::deposit_event(Event::WorldDataModified(game, route, who, change));
+
+			Ok(())
+		}
+
+		/// Register the schema that future writes of a world key must conform to.
+		///
+		/// Values already stored under the key are not checked.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn world_set_schema(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey, kind : ValueKind, min : Option<i128>, max : Option<i128>, default : Option<Sval>) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
+
+			let key = bounded_key::<T>(key)?;
+			let schema = new_schema::<T>(kind, min, max, default)?;
+
+			<WorldSchemas<T>>::insert((game, route, &key), schema);
+
+			Self::deposit_event(Event::WorldSchemaSet(game, route, who, key.into_inner()));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn world_remove_schema(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
+
+			let key = bounded_key::<T>(key)?;
+			ensure!(<WorldSchemas<T>>::contains_key((game, route, &key)), Error::<T>::NotFound);
+
+			<WorldSchemas<T>>::remove((game, route, &key));
+
+			Self::deposit_event(Event::WorldSchemaRemoved(game, route, who, key.into_inner()));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(1).saturating_add(user_remove_weight::<T>()))]
+		pub fn user_remove_data_record(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, entry_key : Skey, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Remove)?;
+			
+			let key = bounded_key::<T>(entry_key)?;
+
+			let change = user_remove::<T>(game, &user, route, &key, expected_revision)?;
+
+			Self::deposit_event(Event::UserDataRemoved(game, user, route, who, change));
+
+			Ok(())
+		}
+
+		/// Create or overwrite a user entry. See `world_update_data_record`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(1).saturating_add(user_write_weight::<T>()).saturating_add(set_expiry_weight::<T>()))]
+		#[transactional]
+		pub fn user_update_data_record(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, entry : DataEntry, expected_revision : Option<u32>, expires_at : Option<T::BlockNumber>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let (key, value) = bounded_entry::<T>(entry)?;
+
+			let change = user_insert::<T>(game, &user, route, &key, &value, &who, expected_revision)?;
+			user_set_expiry::<T>(game, &user, route, &key, expires_at)?;
+
+			Self::deposit_event(Event::UserDataUpdated(game, user, route, who, change));
+
+			Ok(())
+		}
+
+		/// Register the schema that future writes of a user key must conform to, for every player.
+		///
+		/// Values already stored under the key are not checked.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn user_set_schema(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey, kind : ValueKind, min : Option<i128>, max : Option<i128>, default : Option<Sval>) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
+
+			let key = bounded_key::<T>(key)?;
+			let schema = new_schema::<T>(kind, min, max, default)?;
+
+			<UserSchemas<T>>::insert((game, route, &key), schema);
+
+			Self::deposit_event(Event::UserSchemaSet(game, route, who, key.into_inner()));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn user_remove_schema(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
+
+			let key = bounded_key::<T>(key)?;
+			ensure!(<UserSchemas<T>>::contains_key((game, route, &key)), Error::<T>::NotFound);
+
+			<UserSchemas<T>>::remove((game, route, &key));
+
+			Self::deposit_event(Event::UserSchemaRemoved(game, route, who, key.into_inner()));
+
+			Ok(())
+		}
+
+		/// Add a signed 32-bit amount to a user entry. See `world_mod_data_record`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(3).saturating_add(user_write_weight::<T>()))]
+		pub fn user_mod_data_record(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, new_entry : DataEntry, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let key = bounded_key::<T>(new_entry.0)?;
+
+			let change = user_numeric::<T>(game, &user, route, &key, IntKind::I32, NumericOp::Add, &new_entry.1, OverflowPolicy::Checked, &who, expected_revision)?;
+
+			Self::deposit_event(Event::UserDataModified(game, user, route, who, change));
+
+			Ok(())
+		}
+
+		/// Apply an arithmetic operation to an integer user entry of any width.
+		///
+		/// See `world_numeric_op`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(3).saturating_add(user_write_weight::<T>()))]
+		pub fn user_numeric_op(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, key : Skey, kind : IntKind, op : NumericOp, operand : Sval, overflow : OverflowPolicy, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let key = bounded_key::<T>(key)?;
+
+			let change = user_numeric::<T>(game, &user, route, &key, kind, op, &operand, overflow, &who, expected_revision)?;
+
+			Self::deposit_event(Event::UserDataModified(game, user, route, who, change));
+
+			Ok(())
+		}
+
+		/// Write a user entry only if its current value is `expected`. See `world_compare_and_swap`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(2).saturating_add(user_write_weight::<T>()))]
+		pub fn user_compare_and_swap(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, entry : DataEntry, expected : Option<Sval>, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let (key, value) = bounded_entry::<T>(entry)?;
+
+			check_value::<T>(<UserData<T>>::get((game, &user, route, &key)).as_ref(), expected.as_deref())?;
+
+			let change = user_insert::<T>(game, &user, route, &key, &value, &who, expected_revision)?;
+
+			Self::deposit_event(Event::UserDataUpdated(game, user, route, who, change));
+
+			Ok(())
+		}
+
+		/// Set, clear or toggle bits of a user entry in place. See `world_bit_op`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(3).saturating_add(user_write_weight::<T>()))]
+		pub fn user_bit_op(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, key : Skey, op : BitOp, expected_revision : Option<u32>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let key = bounded_key::<T>(key)?;
+
+			let change = user_bits::<T>(game, &user, route, &key, &op, &who, expected_revision)?;
+
+			Self::deposit_event(Event::UserDataModified(game, user, route, who, change));
+
+			Ok(())
+		}
+
+		/// Set how many values each entry of `game` keeps in its history.
+		///
+		/// Lowering the depth trims an entry's history on its next write. A depth of 0 stops
+		/// recording and deletes up to `MaxHistoryRemovals` values of the game's history. While
+		/// `HistoryClearing` lists the game there is more to delete: set the depth to 0 again, as
+		/// the depth can't be raised until the history is gone.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2).saturating_add(
+			T::DbWeight::get().reads_writes(1, 1).saturating_mul(T::MaxHistoryRemovals::get() as Weight)
+		))]
+		pub fn set_history_depth(origin: OriginFor<T>, game : T::GameID, depth : u32) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
+
+			ensure!(depth <= T::MaxHistoryDepth::get(), Error::<T>::HistoryTooDeep);
+
+			if depth == 0
+			{
+				<HistoryDepth<T>>::remove(game);
+
+				if clear_history::<T>(game)
+				{
+					<HistoryClearing<T>>::remove(game);
+				}
+				else
+				{
+					<HistoryClearing<T>>::insert(game, ());
+				}
+			}
+			else
+			{
+				ensure!(!<HistoryClearing<T>>::contains_key(game), Error::<T>::HistoryClearing);
+
+				<HistoryDepth<T>>::insert(game, depth);
+			}
+
+			Self::deposit_event(Event::HistoryDepthSet(game, depth, who));
+
+			Ok(())
+		}
+
+		/// Write back the value a world entry held at an earlier revision, as a new revision.
+		///
+		/// The revision must still be in the entry's history. Works on removed entries too.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(2).saturating_add(world_insert_weight::<T>()))]
+		pub fn world_rollback(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey, revision : u32) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
+
+			let key = bounded_key::<T>(key)?;
+			let value = historic_value::<T>(<WorldHistory<T>>::get((game, route, &key)), revision)?;
+
+			let change = world_insert::<T>(game, route, &key, &value, &who, None)?;
 
-	pub(super) type Skey = Vec<u8>;
-	pub(super) type Sval = Vec<u8>;
-	pub(super) type DataEntry = (Skey,Sval);
-	pub(super) type DataRecord = Vec<DataEntry>;
-	pub(super) type Permission<T> = (<T as self::Config>::GameID, Access);
-	pub(super) type UserID<T> = (<T as self::Config>::GameID, <T as frame_system::Config>::AccountId);
+			Self::deposit_event(Event::WorldDataRolledBack(game, route, who, revision, change));
 
-	#[pallet::storage]
-	pub(super) type WorldDataMap<T: Config> = StorageDoubleMap<_, Twox64Concat, T::GameID, Twox64Concat, Route, DataRecord, ValueQuery>;
+			Ok(())
+		}
 
-	#[pallet::storage]
-	pub(super) type UserDataMap<T: Config> = StorageDoubleMap<_, Twox64Concat, UserID<T>, Twox64Concat, Route, DataRecord, ValueQuery>;
+		/// Write back the value a user entry held at an earlier revision. See `world_rollback`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads(2).saturating_add(user_write_weight::<T>()))]
+		pub fn user_rollback(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, key : Skey, revision : u32) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
 
-	#[pallet::storage]
-	pub(super) type AuthoritiesMap<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, Vec<Permission<T>>, ValueQuery>;
+			let key = bounded_key::<T>(key)?;
+			let value = historic_value::<T>(<UserHistory<T>>::get((game, &user, route, &key)), revision)?;
 
-	// Pallets use events to inform users when important changes are made.
-	// https://docs.substrate.io/v3/runtime/events-and-errors
-	#[pallet::event]
-	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T: Config> {
-		// [current level, who leveled up]
-		LevelUp(u32, T::AccountId),
+			let change = user_insert::<T>(game, &user, route, &key, &value, &who, None)?;
 
-		// [Game world, updated data]
-		WorldDataUpdate(T::GameID, DataEntry)
-	}
+			Self::deposit_event(Event::UserDataRolledBack(game, user, route, who, revision, change));
 
-	// Errors inform users that something went wrong.
-	#[pallet::error]
-	pub enum Error<T> {
-		/// Error names should be descriptive.
-		NoneValue,
-		/// Errors should have helpful documentation associated with them.
-		StorageOverflow,
+			Ok(())
+		}
 
-		AlreadyRegisteredGame,
+		/// Lock or unlock a world entry, or make it write-once.
+		///
+		/// Flags can be set before the entry exists: a write-once entry can then be written once.
+		/// Locked and written write-once entries fail every update, mod, remove and rollback with
+		/// `EntryLocked`. The write-once flag cannot be cleared.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn world_set_entry_flags(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey, flags : EntryFlags) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
 
-		AlreadyRegisteredAuthority,
+			let key = bounded_key::<T>(key)?;
+			let updated = updated_flags::<T>(<WorldEntryFlags<T>>::get((game, route, &key)), flags)?;
 
-		InvalidAuthority,
-		
-		InvalidAccess,
+			match updated {
+				Some(updated) => <WorldEntryFlags<T>>::insert((game, route, &key), updated),
+				None => <WorldEntryFlags<T>>::remove((game, route, &key)),
+			}
 
-		NotFound,
+			Self::deposit_event(Event::WorldEntryFlagsSet(game, route, who, key.into_inner(), flags));
 
-		BadSize,
+			Ok(())
+		}
 
-		Fake
-	}
+		/// Lock or unlock a player's entry, or make it write-once. See `world_set_entry_flags`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn user_set_entry_flags(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, key : Skey, flags : EntryFlags) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
 
-	#[pallet::genesis_config]
-	pub struct GenesisConfig<T: Config> {
-		pub fps_game_authority: T::AccountId,
-		pub fps_game_id: T::GameID,
-		pub platformer_game_authority: T::AccountId,
-		pub platformer_game_id: T::GameID,
-	}
+			let key = bounded_key::<T>(key)?;
+			let updated = updated_flags::<T>(<UserEntryFlags<T>>::get((game, &user, route, &key)), flags)?;
 
-	#[cfg(feature = "std")]
-	impl<T: Config> Default for GenesisConfig<T> {
-		fn default() -> Self {
-			Self { 
-				fps_game_authority: Default::default(),
-				fps_game_id: Default::default(),
-				platformer_game_authority: Default::default(),
-				platformer_game_id: Default::default()
+			match updated {
+				Some(updated) => <UserEntryFlags<T>>::insert((game, &user, route, &key), updated),
+				None => <UserEntryFlags<T>>::remove((game, &user, route, &key)),
 			}
+
+			Self::deposit_event(Event::UserEntryFlagsSet(game, user, route, who, key.into_inner(), flags));
+
+			Ok(())
 		}
-	}
 
-	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
-		fn build(&self) {
+		/// Rank the game's players by the integer value of a user key, keeping the best `size`.
+		///
+		/// The board fills as players' entries are written, by updates, mods, numeric and bit
+		/// operations alike, and players leave it when their entry is removed. Setting a board
+		/// that exists already with another kind or order starts it over. A new board doesn't rank
+		/// entries written before it; see `reseed_leaderboard`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2))]
+		pub fn set_leaderboard(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey, kind : IntKind, order : RankOrder, size : u32) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
 
-			// FPS
-			<AuthoritiesMap<T>>::insert(&self.fps_game_authority, 
-				vec!((self.fps_game_id, Access::InternalExternal)));
+			ensure!(size > 0 && size <= T::MaxLeaderboardSize::get(), Error::<T>::InvalidLeaderboardSize);
 
-			let entry : DataEntry= (
-				String::into_bytes(String::from("Time")),
-				1i32.to_le_bytes().to_vec(),
-			);
+			let key = bounded_key::<T>(key)?;
 
-			<WorldDataMap<T>>::insert(&self.fps_game_id, Route::External, vec!(&entry));
-			
+			let board = match <Leaderboards<T>>::get((game, route, &key)) {
+				Some(mut board) if board.kind == kind && board.order == order => {
+					let mut ranking = board.ranking.clone().into_inner();
 
-			// PLATFORMER
-			<AuthoritiesMap<T>>::insert(&self.platformer_game_authority, 
-				vec!((self.platformer_game_id, Access::InternalExternal)));
+					if let Some(&(_, dropped)) = ranking.get(size as usize)
+					{
+						board.discard(dropped);
+					}
 
-			let entry1 : DataEntry= (
-				String::into_bytes(String::from("Kills")),
-				0u32.to_le_bytes().to_vec(),
-			);
-			let entry2 : DataEntry= (
-				String::into_bytes(String::from("Deaths")),
-				0u32.to_le_bytes().to_vec(),
-			);
+					ranking.truncate(size as usize);
 
-			<WorldDataMap<T>>::insert(&self.platformer_game_id, Route::External, vec!(&entry1,&entry2));
-		}
-	}
+					// Cannot fail: the ranking was no longer than `MaxLeaderboardSize` to begin with.
+					Leaderboard { size, ranking: BoundedVec::try_from(ranking).unwrap_or_default(), ..board }
+				},
+				existing => {
+					if existing.is_none()
+					{
+						<LeaderboardCount<T>>::try_mutate(game, |count| -> Result<(), Error<T>> {
+							ensure!(*count < T::MaxLeaderboards::get(), Error::<T>::TooManyLeaderboards);
+							*count += 1;
+							Ok(())
+						})?;
+					}
 
-	fn is_authority<T: Config>(who : &T::AccountId, game : T::GameID) -> (bool, Access)
-	{
-		let bad_result = (false, Access::default());
+					Leaderboard { kind, order, size, ranking: Default::default(), cutoff: None }
+				},
+			};
 
-		// Are they listed in authorities at all?
-		let permissions = match <AuthoritiesMap<T>>::try_get(who)
-		{
-			Err(_) => {return bad_result;},
-			Ok(p) => p
-		};
+			<Leaderboards<T>>::insert((game, route, &key), board);
 
-		// Do they have authority for the specified game?
-		for p in permissions
-		{
-			if p.0 == game
-			{
-				return (true, p.1);
-			}
+			Self::deposit_event(Event::LeaderboardSet(game, route, who, key.into_inner()));
+
+			Ok(())
 		}
 
-		bad_result
-	}
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2))]
+		pub fn remove_leaderboard(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
 
-	fn game_exists<T: Config>(game : &T::GameID) -> bool
-	{
-		for authority in <AuthoritiesMap<T>>::iter()
+			let key = bounded_key::<T>(key)?;
+			ensure!(<Leaderboards<T>>::contains_key((game, route, &key)), Error::<T>::NotFound);
+
+			<Leaderboards<T>>::remove((game, route, &key));
+			<LeaderboardCount<T>>::mutate_exists(game, shrink_record);
+
+			Self::deposit_event(Event::LeaderboardRemoved(game, route, who, key.into_inner()));
+
+			Ok(())
+		}
+
+		/// Rank `players` from scratch by their current entries, forgetting the board's cutoff.
+		///
+		/// Lets a new board start from the scores players already have, and a board whose cutoff
+		/// keeps players off rank them again. List the best players of the game, at most the board's
+		/// size: players left out are only ranked once their entry is written again.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1).saturating_add(T::DbWeight::get().reads(players.len() as Weight)))]
+		pub fn reseed_leaderboard(origin: OriginFor<T>, game : T::GameID, route : Route, key : Skey, players : Vec<T::AccountId>) -> DispatchResult
 		{
-			let permissions = authority.1;
+			let (who, _) = is_manager_call::<T>(origin, game)?;
+
+			let key = bounded_key::<T>(key)?;
+			let mut board = <Leaderboards<T>>::get((game, route, &key)).ok_or(Error::<T>::NotFound)?;
+			ensure!(players.len() <= board.size as usize, Error::<T>::TooManyPlayers);
 
-			if permissions.iter().any(|e| e.0 == *game)
+			board.ranking = Default::default();
+			board.cutoff = None;
+
+			for player in players.iter()
 			{
-				return true;
+				let value = <UserData<T>>::get((game, player, route, &key)).map(|entry| entry.value);
+				board.rank(player, value.as_ref().map(|value| &value[..]));
 			}
-		}
 
-		false
-	}
+			<Leaderboards<T>>::insert((game, route, &key), board);
 
-	fn is_authorized_call<T: Config> (origin: OriginFor<T>, game : T::GameID, route : Route) -> Result<T::AccountId, sp_runtime::DispatchError>
-	{
-		let who = ensure_signed(origin)?;
+			Self::deposit_event(Event::LeaderboardReseeded(game, route, who, key.into_inner()));
 
-		let who_auth = is_authority::<T>(&who, game);
-		
-		ensure!(who_auth.0, Error::<T>::InvalidAuthority);
+			Ok(())
+		}
 
-		if route == Route::Internal
+		/// Register a rule setting the game's world entry `entry` whenever the external world entry
+		/// `watched_key` of `watched_game` crosses `threshold`, read as an integer of `kind`.
+		///
+		/// Rules fire on the write that crosses the threshold, not while it stays crossed; a value
+		/// that doesn't read as `kind` never crosses it. The entry is written on behalf of the
+		/// caller and is subject to its schema and flags; a failed write emits `TriggerFailed`
+		/// without failing the watched write. Writes made by rules don't fire further rules.
+		///
+		/// A game can have at most `MaxGameTriggersPerKey` rules watching the same entry. A rule
+		/// stops writing, and fails, once the caller can no longer write `route`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 4))]
+		pub fn register_trigger(origin: OriginFor<T>, game : T::GameID, watched_game : T::GameID, watched_key : Skey, kind : IntKind, threshold : Threshold, route : Route, entry : DataEntry) -> DispatchResult
 		{
-			ensure!(who_auth.1 == Access::InternalExternal, Error::<T>::InvalidAccess)
-		}
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
 
-		Ok(who)
-	}
+			ensure!(<Games<T>>::contains_key(watched_game), Error::<T>::UnknownGame);
 
-	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
-	// These functions materialize as "extrinsics", which are often compared to transactions.
-	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
+			let watched_key = bounded_key::<T>(watched_key)?;
+			let (key, value) = bounded_entry::<T>(entry)?;
+			check_world_schema::<T>(game, route, &key, &value)?;
 
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
-		pub fn world_remove_data_record(origin: OriginFor<T>, game : T::GameID, entry : DataEntry, route : Route) -> DispatchResult
-		{
-			is_authorized_call::<T>(origin, game, route)?;
-			
-			ensure!(<WorldDataMap<T>>::contains_key(game, route), Error::<T>::NotFound);
-			
-			<WorldDataMap<T>>::mutate(game, route, |x| { 
-				match x.iter().position(|cur_entry| cur_entry.0 == entry.0) {
-					None => Err(Error::<T>::NotFound)?,	
-					Some(d) => { 
-						x.swap_remove(d); 
-						Ok(())
-					}
-				}
-			})
+			ensure!(<TriggerCount<T>>::get(game) < T::MaxTriggers::get(), Error::<T>::TooManyTriggers);
+
+			let id = <NextTriggerId<T>>::get(game);
+			let rule = TriggerRule { game, id, creator: who.clone(), kind, threshold, route, key, value };
+
+			<Triggers<T>>::try_mutate(watched_game, &watched_key, |rules| -> Result<(), Error<T>> {
+				let own = rules.iter().filter(|rule| rule.game == game).count();
+				ensure!(own < T::MaxGameTriggersPerKey::get() as usize, Error::<T>::TooManyTriggers);
+
+				rules.try_push(rule).map_err(|_| Error::<T>::TooManyTriggers)
+			})?;
+
+			<TriggerWatches<T>>::insert(game, id, (watched_game, watched_key));
+			<TriggerCount<T>>::mutate(game, |count| *count += 1);
+			<NextTriggerId<T>>::insert(game, id.wrapping_add(1));
+
+			Self::deposit_event(Event::TriggerRegistered(game, id, watched_game, who));
+
+			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn world_update_data_record(origin: OriginFor<T>, game : T::GameID, entry : DataEntry, route : Route) -> DispatchResult
+		/// Remove a trigger rule. Besides the authorities that can write the rule's route, the
+		/// managers of the watched game can remove any rule watching its entries.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(4, 3))]
+		pub fn remove_trigger(origin: OriginFor<T>, game : T::GameID, id : u32) -> DispatchResult
 		{
-			is_authorized_call::<T>(origin, game, route)?;
+			let who = ensure_signed(origin)?;
+			let (watched_game, watched_key) = <TriggerWatches<T>>::get(game, id).ok_or(Error::<T>::NotFound)?;
+
+			let mut rules = <Triggers<T>>::get(watched_game, &watched_key);
+			let index = rules.iter().position(|rule| rule.game == game && rule.id == id).ok_or(Error::<T>::NotFound)?;
 
-			if ! <WorldDataMap<T>>::contains_key(game, route)
+			let allowed = ensure_can_write::<T>(&who, game, rules[index].route).is_ok()
+				|| authority_role::<T>(&who, watched_game).map_or(false, |role| role.is_manager());
+			ensure!(allowed, Error::<T>::InvalidAccess);
+
+			rules.remove(index);
+
+			if rules.is_empty()
 			{
-				<WorldDataMap<T>>::insert(game, route, vec!(&entry));
+				<Triggers<T>>::remove(watched_game, &watched_key);
 			}
 			else
 			{
-				<WorldDataMap<T>>::mutate(game, route, |record| {
+				<Triggers<T>>::insert(watched_game, &watched_key, rules);
+			}
 
-					match record.iter().position(|cur_entry| cur_entry.0 == (&entry).0) {
-						
-						// Generate a new entry.
-						None => { record.push(entry.clone()); },
-						
-						// Assign new value to existing entry.
-						Some(index) => { record[index].1 = (&entry).1.clone(); }
-					};
+			<TriggerWatches<T>>::remove(game, id);
+			<TriggerCount<T>>::mutate_exists(game, shrink_record);
 
-				});
-			}
+			Self::deposit_event(Event::TriggerRemoved(game, id, who));
 
-			// Emit an event.
-			Self::deposit_event(Event::WorldDataUpdate(game, entry));
-			
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn world_mod_data_record(origin: OriginFor<T>, game : T::GameID, new_entry : DataEntry, route : Route, ) -> DispatchResult
+		/// Declare how the game's world time runs, replacing any clock it had.
+		///
+		/// In-world time counts from the timestamp `epoch` at `speed` in-world milliseconds per
+		/// thousand real ones. `phases` are `(name, start)` pairs splitting the day of `day_length`
+		/// in-world milliseconds, in order of their start. See the `clock` module.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_world_clock(origin: OriginFor<T>, game : T::GameID, epoch : MomentOf<T>, speed : u32, day_length : u64, phases : Vec<(Vec<u8>, u64)>) -> DispatchResult
 		{
-			is_authorized_call::<T>(origin, game, route)?;
+			let (who, _) = is_manager_call::<T>(origin, game)?;
 
-			ensure!(<WorldDataMap<T>>::contains_key(game, route), Error::<T>::NotFound);
-			
-			<WorldDataMap<T>>::mutate(game, route, |record :&mut DataRecord| {
+			let phases = phases.into_iter()
+				.map(|(name, start)| Ok(ClockPhase { name: name.try_into()?, start }))
+				.collect::<Result<Vec<_>, ()>>()
+				.ok()
+				.and_then(|phases| BoundedVec::try_from(phases).ok())
+				.ok_or(Error::<T>::InvalidClock)?;
 
-				if false //HACK. IDK why I need this. Compiler compaints.
-				{
-					return Err(Error::<T>::Fake);
-				}
-				
-				let index : usize = record.iter().position(|cur_entry : &DataEntry| cur_entry.0 == (&new_entry).0).ok_or(Error::<T>::NotFound)?;
-				
-				let exisiting_entry = &mut record[index];
-
-				//Ensure same length
-				const SUPPORTED_BYTE_LENGTH : usize = 4;
-				ensure!(exisiting_entry.1.len() == SUPPORTED_BYTE_LENGTH, Error::<T>::BadSize);
-				ensure!(new_entry.1.len() == SUPPORTED_BYTE_LENGTH, Error::<T>::BadSize);
-				
-				// Interpret existing numeric value.
-				let existing_numeric : i32;
-				{
-					let mut vec_data : [u8; SUPPORTED_BYTE_LENGTH] = Default::default();
-					vec_data.copy_from_slice(&exisiting_entry.1[0..SUPPORTED_BYTE_LENGTH]);
-					existing_numeric = i32::from_le_bytes(vec_data);
-				}
+			let clock : WorldClockOf<T> = WorldClock { epoch, speed, day_length, phases };
+			ensure!(clock.is_valid::<PhaseName<T>>(), Error::<T>::InvalidClock);
 
-				// Interpret incoming numberic value.
-				let incoming_numeric : i32;
-				{
-					let mut vec_data : [u8; SUPPORTED_BYTE_LENGTH] = Default::default();
-					vec_data.copy_from_slice(&new_entry.1[0..SUPPORTED_BYTE_LENGTH]);
-					incoming_numeric = i32::from_le_bytes(vec_data);
-				}				
+			<WorldClocks<T>>::insert(game, clock);
+
+			Self::deposit_event(Event::WorldClockSet(game, who));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn remove_world_clock(origin: OriginFor<T>, game : T::GameID) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
+
+			ensure!(<WorldClocks<T>>::contains_key(game), Error::<T>::NotFound);
+
+			<WorldClocks<T>>::remove(game);
+
+			Self::deposit_event(Event::WorldClockRemoved(game, who));
+
+			Ok(())
+		}
 
-				// Increment|Decrement value.
-				let result_numeric = existing_numeric + incoming_numeric;
-				let result_numeric = result_numeric.to_le_bytes();
+		/// Schedule a world entry to be set to `entry`'s value at `start`.
+		///
+		/// With `end`, the entry goes back to the value it had before `start`, or is removed if it
+		/// had none. With `period`, the change repeats every `period` blocks; it must end before it
+		/// repeats. `start` and `end` must both be blocks or both be timestamps.
+		///
+		/// The change is written on behalf of the caller without a revision check, and is subject to
+		/// the entry's schema and flags when it fires. It fails to apply once the caller can no
+		/// longer write `route`, but a change in effect is still reverted. A change that fails to
+		/// apply emits `ScheduledChangeFailed` and carries on with its schedule.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(5, 4))]
+		#[transactional]
+		pub fn schedule_world_change(origin: OriginFor<T>, game : T::GameID, route : Route, entry : DataEntry, start : ScheduleTimeOf<T>, end : Option<ScheduleTimeOf<T>>, period : Option<T::BlockNumber>) -> DispatchResult
+		{
+			let who = is_authorized_call::<T>(origin, game, route, Action::Write)?;
 
-				exisiting_entry.1 = result_numeric.to_vec(); 
+			let (key, value) = bounded_entry::<T>(entry)?;
+			check_world_schema::<T>(game, route, &key, &value)?;
+			check_schedule::<T>(start, end, period)?;
 
+			<ScheduleCount<T>>::try_mutate(game, |count| -> Result<(), Error<T>> {
+				ensure!(*count < T::MaxSchedules::get(), Error::<T>::TooManySchedules);
+				*count += 1;
 				Ok(())
 			})?;
 
-			// // Emit an event.
-			// Self::deposit_event(Event::WorldDataUpdate(game, new_entry));
-			
+			let id = <NextScheduleId<T>>::mutate(game, |next| {
+				let id = *next;
+				*next = next.wrapping_add(1);
+				id
+			});
+
+			queue_schedule::<T>(game, id, start)?;
+
+			let schedule = ScheduledChange {
+				creator: who.clone(),
+				route,
+				key: key.clone(),
+				value,
+				start,
+				end,
+				period,
+				active: None,
+			};
+
+			<Schedules<T>>::insert(game, id, schedule);
+
+			Self::deposit_event(Event::ChangeScheduled(game, id, route, who, key.into_inner()));
+
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn user_remove_data_record(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, entry_key : Skey) -> DispatchResult
+		/// Cancel a scheduled change. A change in effect is left in place, not reverted.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn cancel_scheduled_change(origin: OriginFor<T>, game : T::GameID, id : u32) -> DispatchResult
 		{
-			is_authorized_call::<T>(origin, game, route)?;
-			
-			let map_key = (game, user);
+			let schedule = <Schedules<T>>::get(game, id).ok_or(Error::<T>::NotFound)?;
+			let who = is_authorized_call::<T>(origin, game, schedule.route, Action::Write)?;
 
-			ensure!(<UserDataMap<T>>::contains_key(&map_key, route), Error::<T>::NotFound);
-			
-			<UserDataMap<T>>::mutate(&map_key, route, |x| { 
-				match x.iter().position(|cur_entry| cur_entry.0 == entry_key) {
-					None => Err(Error::<T>::NotFound)?,	
-					Some(d) => { 
-						x.swap_remove(d); 
-						Ok(())
-					}
-				}
-			})
+			unqueue_schedule::<T>(game, id, schedule.next_time());
+			<Schedules<T>>::remove(game, id);
+			<ScheduleCount<T>>::mutate_exists(game, shrink_record);
+
+			Self::deposit_event(Event::ScheduledChangeCancelled(game, id, who));
+
+			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn user_update_data_record(origin: OriginFor<T>, game : T::GameID, user : T::AccountId, route : Route, entry : DataEntry) -> DispatchResult
+		/// Apply several world and user operations of one game, all or nothing.
+		///
+		/// The caller's role is looked up once and must allow every operation's route and action
+		/// before anything is written. If any operation fails, none of them take effect.
+		#[pallet::weight(10_000 + (ops.len() as Weight).saturating_mul(10_000 + batch_op_weight::<T>()))]
+		#[transactional]
+		pub fn batch(origin: OriginFor<T>, game : T::GameID, ops : Vec<BatchOp<T::AccountId, T::BlockNumber>>) -> DispatchResult
 		{
-			is_authorized_call::<T>(origin, game, route)?;
+			ensure!(!ops.is_empty() && ops.len() <= T::MaxBatchOps::get() as usize, Error::<T>::BadBatchSize);
 
-			let map_key = (game, user);
+			let (who, role) = is_authority_call::<T>(origin, game)?;
 
-			if ! <UserDataMap<T>>::contains_key(&map_key, route)
+			for op in ops.iter()
 			{
-				<UserDataMap<T>>::insert(&map_key, route, vec!(entry));
+				ensure_allowed::<T>(role, op.route(), op.action())?;
 			}
-			else
+
+			let count = ops.len() as u32;
+
+			for op in ops
 			{
-				<UserDataMap<T>>::mutate(&map_key, route, |x| { 
-					match x.iter().position(|cur_entry| cur_entry.0 == entry.0) {
-						None => { x.push(entry); },
-						Some(d) => { x[d].1 = entry.1; }
-					};
-				});
+				apply_batch_op::<T>(game, &who, op)?;
 			}
 
+			Self::deposit_event(Event::BatchApplied(game, who, count));
+
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 3))]
 		pub fn register_game(origin: OriginFor<T>, game : T::GameID) -> DispatchResult
 		{
 			let who = ensure_signed(origin)?;
 
 			frame_support::ensure!(! game_exists::<T>(&game), Error::<T>::AlreadyRegisteredGame);
+			frame_support::ensure!(<Authorities<T>>::iter_key_prefix(game).next().is_none(), Error::<T>::GameHasAuthorities);
 			
-			// For the registring user, access the permissions
-			let new_entry = (game, Access::InternalExternal);
-			
-			// When a user first registers a game, they don't exist in the AuthoritiesMap. Add them.
-			if <AuthoritiesMap<T>>::contains_key(&who)
-			{
-				//TODO: use try_mutate.
-				<AuthoritiesMap<T>>::mutate(&who, |x| {
-					x.push(new_entry);
-				});
-			}
-			else
-			{
-				<AuthoritiesMap<T>>::insert(&who, vec!(new_entry));
-			};
+			// The registering user becomes the game's owner.
+			grant_access::<T>(game, &who, Role::Owner);
+
+			register::<T>(game, &who, Vec::new());
+
+			Self::deposit_event(Event::GameRegistered(game, who));
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
-		pub fn add_authority(origin: OriginFor<T>, game : T::GameID, new_authority : T::AccountId, access : Access) -> DispatchResult
-		{			
-			let who = ensure_signed(origin)?;
+		/// Register a game for `owner`, even if it has authorities already.
+		///
+		/// For games whose authorities predate the registry, but whose upgrade found no authority
+		/// to make its owner. See `migrations::v4`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 3))]
+		pub fn force_register_game(origin: OriginFor<T>, game : T::GameID, owner : T::AccountId) -> DispatchResult
+		{
+			ensure_root(origin)?;
+
+			frame_support::ensure!(! game_exists::<T>(&game), Error::<T>::AlreadyRegisteredGame);
+
+			// Replaces any role the owner held before.
+			grant_access::<T>(game, &owner, Role::Owner);
 
-			// Ensure only authorities add other authorities.
-			let who_auth = is_authority::<T>(&who, game);
-			frame_support::ensure!(who_auth.0, Error::<T>::InvalidAuthority);
+			register::<T>(game, &owner, Vec::new());
+
+			Self::deposit_event(Event::GameRegistered(game, owner));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn set_game_info(origin: OriginFor<T>, game : T::GameID, name : Vec<u8>, description : Vec<u8>, website : Vec<u8>, icon : Option<T::Hash>, genres : Vec<Vec<u8>>) -> DispatchResult
+		{
+			let (who, _) = is_manager_call::<T>(origin, game)?;
+
+			let name : BoundedVec<u8, T::MaxNameLen> =
+				name.try_into().map_err(|_| Error::<T>::NameTooLong)?;
+			let description : BoundedVec<u8, T::MaxDescriptionLen> =
+				description.try_into().map_err(|_| Error::<T>::DescriptionTooLong)?;
+			let website : BoundedVec<u8, T::MaxWebsiteLen> =
+				website.try_into().map_err(|_| Error::<T>::WebsiteTooLong)?;
+
+			let genres : BoundedVec<BoundedVec<u8, T::MaxGenreLen>, T::MaxGenres> = genres.into_iter()
+				.map(|genre| BoundedVec::try_from(genre).map_err(|_| Error::<T>::GenreTooLong))
+				.collect::<Result<Vec<_>, _>>()?
+				.try_into()
+				.map_err(|_| Error::<T>::TooManyGenres)?;
+
+			<Games<T>>::try_mutate(game, |info| -> DispatchResult {
+				let info = info.as_mut().ok_or(Error::<T>::UnknownGame)?;
+
+				info.name = name;
+				info.description = description;
+				info.website = website;
+				info.icon = icon;
+				info.genres = genres;
+
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::GameInfoUpdated(game, who));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2))]
+		pub fn add_authority(origin: OriginFor<T>, game : T::GameID, new_authority : T::AccountId, role : Role) -> DispatchResult
+		{			
+			// Ensure only managers add other authorities, and only below their own rank.
+			let (who, who_role) = is_manager_call::<T>(origin, game)?;
+			frame_support::ensure!(who_role.can_manage(role), Error::<T>::InvalidAccess);
 
 			// Ensure new authority is not already registered
-			let new_auth = is_authority::<T>(&new_authority, game);
-			frame_support::ensure!(!new_auth.0, Error::<T>::InvalidAuthority);
+			frame_support::ensure!(! <Authorities<T>>::contains_key(game, &new_authority), Error::<T>::AlreadyRegisteredAuthority);
 
-			// Does new authority have a map entry?
-			match <AuthoritiesMap<T>>::try_get(new_authority.clone()) //Hack: workaround for borrowing in match arms.
-			{
-				// Update entry.
-				Ok(_) => {
-					<AuthoritiesMap<T>>::mutate(&new_authority, |x| {
-						let entry = (game,access);
-						x.push(entry);
-					});
-				},
+			grant_access::<T>(game, &new_authority, role);
 
-				// Create entry.
-				Err(_) => {
-					let new_entry = (game, access);
-					<AuthoritiesMap<T>>::insert(&new_authority, vec!(new_entry));
-				}
-			};
+			Self::deposit_event(Event::AuthorityAdded(game, new_authority, role, who));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn set_authority_role(origin: OriginFor<T>, game : T::GameID, authority : T::AccountId, role : Role) -> DispatchResult
+		{
+			let (who, who_role) = is_manager_call::<T>(origin, game)?;
+
+			let current = authority_role::<T>(&authority, game)?;
+			frame_support::ensure!(current != Role::Owner, Error::<T>::OwnerRequired);
+
+			// Managers may only move authorities around below their own rank.
+			frame_support::ensure!(who_role.can_manage(current) && who_role.can_manage(role), Error::<T>::InvalidAccess);
+
+			<Authorities<T>>::insert(game, &authority, role);
+
+			Self::deposit_event(Event::AuthorityChanged(game, authority, role, who));
 
 			Ok(())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 2))]
 		pub fn remove_authority(origin: OriginFor<T>, game : T::GameID, removed_authority : T::AccountId) -> DispatchResult
 		{			
 			let who = ensure_signed(origin)?;
 
 			// Ensure only authorities remove authorities.
-			let who_auth = is_authority::<T>(&who, game);
-			frame_support::ensure!(who_auth.0, Error::<T>::InvalidAuthority);
+			let who_role = authority_role::<T>(&who, game)?;
 
-			// If removing auhtority other than self...
+			// Ensure removed authority is already registered
+			let removed_role = authority_role::<T>(&removed_authority, game)?;
+
+			// A game can never be left without an owner.
+			frame_support::ensure!(removed_role != Role::Owner, Error::<T>::OwnerRequired);
+
+			// Anyone may step down. Removing someone else takes a manager of higher rank.
 			if who != removed_authority
 			{
-				// Ensure removed authority is already registered
-				let new_auth = is_authority::<T>(&removed_authority, game);
-				frame_support::ensure!(new_auth.0, Error::<T>::InvalidAuthority);
+				frame_support::ensure!(who_role.can_manage(removed_role), Error::<T>::InvalidAccess);
 			}
 
-			// Mutate for removal & return Result. We should not error here at this point.
-			<AuthoritiesMap<T>>::mutate(&removed_authority, |x| {
-				let index = x.iter().position(|p| p.0 == game).ok_or(Error::<T>::InvalidAuthority)?;
-				x.swap_remove(index); 
-				Ok(())
-			})
+			revoke_access::<T>(game, &removed_authority);
+
+			Self::deposit_event(Event::AuthorityRemoved(game, removed_authority, who));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn transfer_ownership(origin: OriginFor<T>, game : T::GameID, new_owner : T::AccountId) -> DispatchResult
+		{
+			let who = ensure_signed(origin)?;
+
+			frame_support::ensure!(authority_role::<T>(&who, game)? == Role::Owner, Error::<T>::InvalidAccess);
+
+			// Offering ownership again replaces any earlier offer.
+			<PendingOwner<T>>::insert(game, &new_owner);
+
+			Self::deposit_event(Event::OwnershipTransferProposed(game, who, new_owner));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn cancel_ownership_transfer(origin: OriginFor<T>, game : T::GameID) -> DispatchResult
+		{
+			let who = ensure_signed(origin)?;
+
+			frame_support::ensure!(authority_role::<T>(&who, game)? == Role::Owner, Error::<T>::InvalidAccess);
+			frame_support::ensure!(<PendingOwner<T>>::contains_key(game), Error::<T>::NotFound);
+
+			<PendingOwner<T>>::remove(game);
+
+			Self::deposit_event(Event::OwnershipTransferCancelled(game, who));
+
+			Ok(())
+		}
+
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 6))]
+		pub fn accept_ownership(origin: OriginFor<T>, game : T::GameID) -> DispatchResult
+		{
+			let who = ensure_signed(origin)?;
+
+			frame_support::ensure!(<PendingOwner<T>>::get(game).as_ref() == Some(&who), Error::<T>::NotPendingOwner);
+
+			let previous_owner = <Games<T>>::try_mutate(game, |info| -> Result<T::AccountId, sp_runtime::DispatchError> {
+				let info = info.as_mut().ok_or(Error::<T>::UnknownGame)?;
+				Ok(sp_std::mem::replace(&mut info.owner, who.clone()))
+			})?;
+
+			// The previous owner stays on the team as an admin.
+			<PendingOwner<T>>::remove(game);
+			grant_access::<T>(game, &previous_owner, Role::Admin);
+			grant_access::<T>(game, &who, Role::Owner);
+
+			Self::deposit_event(Event::OwnershipTransferred(game, previous_owner, who));
+
+			Ok(())
 		}
 	}
 }
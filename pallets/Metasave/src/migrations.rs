@@ -0,0 +1,520 @@
+//! Storage migrations for the Metasave pallet.
+
+use crate::pallet::{
+	grant_access, player_entry_added, player_entry_removed, Authorities, AuthorityGames, BoundedKey, BoundedValue, Config,
+	Entry, GameInfo, Games, Pallet, Role, Route, StoredEntry, UserData, UserRecordLen, WorldData,
+	WorldRecordLen,
+};
+use frame_support::{
+	log,
+	pallet_prelude::*,
+	storage::{
+		migration::{remove_storage_prefix, storage_iter, storage_key_iter},
+		unhashed,
+		StoragePrefixedMap,
+	},
+	traits::{PalletInfoAccess, StorageVersion},
+};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+const LOG_TARGET : &str = "runtime::metasave";
+
+/// Run every migration the on-chain storage version hasn't seen yet.
+pub fn migrate<T: Config>() -> Weight
+{
+	let on_chain = StorageVersion::get::<Pallet<T>>();
+	let mut weight = T::DbWeight::get().reads(1);
+
+	if on_chain < 1
+	{
+		weight = weight.saturating_add(v1::migrate::<T>());
+	}
+
+	if on_chain < 2
+	{
+		weight = weight.saturating_add(v2::migrate::<T>());
+	}
+
+	if on_chain < 3
+	{
+		weight = weight.saturating_add(v3::migrate::<T>());
+	}
+
+	if on_chain < 4
+	{
+		weight = weight.saturating_add(v4::migrate::<T>());
+	}
+
+	if on_chain < 5
+	{
+		weight = weight.saturating_add(v5::migrate::<T>());
+	}
+
+	if on_chain < 6
+	{
+		weight = weight.saturating_add(v6::migrate::<T>());
+	}
+
+	if on_chain < 7
+	{
+		weight = weight.saturating_add(v7::migrate::<T>());
+	}
+
+	if on_chain < 8
+	{
+		weight = weight.saturating_add(v8::migrate::<T>());
+	}
+
+	weight
+}
+
+/// Decode the `(Twox64Concat, Twox64Concat)` key suffix of a pre-migration double map.
+fn decode_double_key<K1: Decode, K2: Decode>(raw_key : &[u8]) -> Option<(K1, K2)>
+{
+	let mut input = raw_key.get(8..)?;
+	let k1 = K1::decode(&mut input).ok()?;
+	let mut input = input.get(8..)?;
+	let k2 = K2::decode(&mut input).ok()?;
+	Some((k1, k2))
+}
+
+/// The permission level an authority held before v6 replaced it with `Role`.
+#[derive(Encode, Decode, Clone, Copy, PartialEq)]
+pub enum OldAccess {
+	External,
+	InternalExternal,
+}
+
+const OLD_AUTHORITIES_MAP : &[u8] = b"AuthoritiesMap";
+
+/// Every account's permissions as stored in `AuthoritiesMap` before v5.
+fn old_authorities<T: Config>() -> Vec<(T::AccountId, Vec<(T::GameID, OldAccess)>)>
+{
+	let pallet = <Pallet<T>>::name().as_bytes();
+	storage_key_iter::<T::AccountId, Vec<(T::GameID, OldAccess)>, Twox64Concat>(pallet, OLD_AUTHORITIES_MAP)
+		.collect()
+}
+
+/// Decode the game and player of a `UserData` key suffix.
+fn decode_user_key<T: Config>(raw_key : &[u8]) -> Option<(T::GameID, T::AccountId)>
+{
+	let mut input = raw_key.get(8..)?;
+	let game = T::GameID::decode(&mut input).ok()?;
+	let mut input = input.get(16..)?;
+	let user = T::AccountId::decode(&mut input).ok()?;
+	Some((game, user))
+}
+
+/// Drop every value of a data map that is longer than `MaxValueLen`, logging each one and passing
+/// its key suffix to `dropped`.
+///
+/// Works on raw storage, because the map's value type has changed since.
+fn drop_oversized_values<T: Config, M: StoragePrefixedMap<StoredEntry<T>>>(mut dropped : impl FnMut(&[u8])) -> (Weight, Weight)
+{
+	let pallet = <Pallet<T>>::name().as_bytes();
+	let map = sp_std::str::from_utf8(M::storage_prefix()).unwrap_or_default();
+	let max = T::MaxValueLen::get() as usize;
+
+	let mut reads : Weight = 0;
+
+	let oversized : Vec<(Vec<u8>, usize)> = storage_iter::<Vec<u8>>(pallet, M::storage_prefix())
+		.inspect(|_| reads += 1)
+		.filter(|(_, value)| value.len() > max)
+		.map(|(raw_key, value)| (raw_key, value.len()))
+		.collect();
+
+	for (raw_key, len) in oversized.iter()
+	{
+		log::warn!(
+			target: LOG_TARGET,
+			"dropping {} entry 0x{:?}: its value is {} bytes, over MaxValueLen",
+			map, HexKey(raw_key), len,
+		);
+
+		unhashed::kill(&[&M::final_prefix()[..], raw_key].concat());
+		dropped(raw_key);
+	}
+
+	if !oversized.is_empty()
+	{
+		log::warn!(target: LOG_TARGET, "dropped {} {} entries over MaxValueLen", oversized.len(), map);
+	}
+
+	(reads, oversized.len() as Weight)
+}
+
+/// Log an entry of an old record that is dropped because its key is longer than `MaxKeyLen`.
+fn log_dropped_key(map : &str, raw_key : &[u8], key : &[u8])
+{
+	log::warn!(
+		target: LOG_TARGET,
+		"dropping {} entry 0x{:?} of record 0x{:?}: its key is {} bytes, over MaxKeyLen",
+		map, HexKey(key), HexKey(raw_key), key.len(),
+	);
+}
+
+/// Formats raw keys as hex in migration logs.
+struct HexKey<'a>(&'a [u8]);
+
+impl<'a> sp_std::fmt::Debug for HexKey<'a> {
+	fn fmt(&self, f : &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result
+	{
+		self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+	}
+}
+
+/// Rewrite every value of a data map from `O` to `N`.
+///
+/// Works on raw storage, because the map's value type has changed since. Returns the number of
+/// values rewritten.
+fn translate_raw<T: Config, M: StoragePrefixedMap<StoredEntry<T>>, O: Decode, N: Encode>(mut f : impl FnMut(O) -> N) -> Weight
+{
+	let pallet = <Pallet<T>>::name().as_bytes();
+
+	let values : Vec<(Vec<u8>, O)> = storage_iter::<O>(pallet, M::storage_prefix()).collect();
+	let count = values.len() as Weight;
+
+	for (raw_key, value) in values
+	{
+		unhashed::put(&[&M::final_prefix()[..], &raw_key].concat(), &f(value));
+	}
+
+	count
+}
+
+/// A world or user entry as stored from v7 until v8 added who wrote it and when.
+#[derive(Encode, Decode)]
+pub struct RevisionedValue<Value> {
+	pub value: Value,
+	pub revision: u32,
+}
+
+/// v1: split `WorldDataMap` records into one `WorldData` entry per key.
+pub mod v1 {
+	use super::*;
+
+	/// Before v1 every entry of a `(GameID, Route)` pair lived in a single vector.
+	type OldDataRecord = Vec<(Vec<u8>, Vec<u8>)>;
+
+	const OLD_WORLD_DATA_MAP : &[u8] = b"WorldDataMap";
+
+	pub fn migrate<T: Config>() -> Weight
+	{
+		let pallet = <Pallet<T>>::name().as_bytes();
+		let mut reads : Weight = 0;
+		let mut writes : Weight = 0;
+
+		let records : Vec<(Vec<u8>, OldDataRecord)> =
+			storage_iter::<OldDataRecord>(pallet, OLD_WORLD_DATA_MAP).collect();
+
+		for (raw_key, record) in records
+		{
+			reads += 1;
+
+			let (game, route) = match decode_double_key::<T::GameID, Route>(&raw_key) {
+				Some(k) => k,
+				None => continue,
+			};
+
+			for (key, value) in record
+			{
+				// Keys past `MaxKeyLen` could not be addressed after v3 anyway.
+				if key.len() > T::MaxKeyLen::get() as usize
+				{
+					log_dropped_key("WorldDataMap", &raw_key, &key);
+					continue;
+				}
+
+				let key = match BoundedKey::<T>::try_from(key) {
+					Ok(key) => key,
+					Err(_) => continue,
+				};
+
+				unhashed::put(&<WorldData<T>>::hashed_key_for((game, route, &key)), &value);
+				writes += 1;
+			}
+		}
+
+		remove_storage_prefix(pallet, OLD_WORLD_DATA_MAP, &[]);
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(reads, writes + 2)
+	}
+}
+
+/// v2: split `UserDataMap` records into one `UserData` entry per key and build the player indexes.
+pub mod v2 {
+	use super::*;
+
+	type OldDataRecord = Vec<(Vec<u8>, Vec<u8>)>;
+
+	const OLD_USER_DATA_MAP : &[u8] = b"UserDataMap";
+
+	pub fn migrate<T: Config>() -> Weight
+	{
+		let pallet = <Pallet<T>>::name().as_bytes();
+		let mut reads : Weight = 0;
+		let mut writes : Weight = 0;
+
+		let records : Vec<(Vec<u8>, OldDataRecord)> =
+			storage_iter::<OldDataRecord>(pallet, OLD_USER_DATA_MAP).collect();
+
+		for (raw_key, record) in records
+		{
+			reads += 1;
+
+			let ((game, user), route) =
+				match decode_double_key::<(T::GameID, T::AccountId), Route>(&raw_key) {
+					Some(k) => k,
+					None => continue,
+				};
+
+			for (key, value) in record
+			{
+				if key.len() > T::MaxKeyLen::get() as usize
+				{
+					log_dropped_key("UserDataMap", &raw_key, &key);
+					continue;
+				}
+
+				let key = match BoundedKey::<T>::try_from(key) {
+					Ok(key) => key,
+					Err(_) => continue,
+				};
+
+				if !<UserData<T>>::contains_key((game, &user, route, &key))
+				{
+					player_entry_added::<T>(game, &user);
+					writes += 2;
+				}
+
+				unhashed::put(&<UserData<T>>::hashed_key_for((game, &user, route, &key)), &value);
+				reads += 1;
+				writes += 1;
+			}
+		}
+
+		remove_storage_prefix(pallet, OLD_USER_DATA_MAP, &[]);
+		StorageVersion::new(2).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(reads, writes + 2)
+	}
+}
+
+/// v3: bound keys and values, and count the entries of every record.
+///
+/// Values beyond the new limits are dropped; entries whose key is longer than `MaxKeyLen` were
+/// already skipped by v1 and v2. Every dropped entry is logged with a warning, and dropped user
+/// entries are taken out of the player indexes v2 built.
+pub mod v3 {
+	use super::*;
+
+	pub fn migrate<T: Config>() -> Weight
+	{
+		let (mut reads, mut writes) = drop_oversized_values::<T, WorldData<T>>(|_| ());
+
+		let (r, w) = drop_oversized_values::<T, UserData<T>>(|raw_key| {
+			if let Some((game, user)) = decode_user_key::<T>(raw_key)
+			{
+				player_entry_removed::<T>(game, &user);
+			}
+		});
+
+		// Each dropped user entry also updates both player indexes.
+		reads += r + w;
+		writes += w * 3;
+
+		for (game, route, _) in <WorldData<T>>::iter_keys()
+		{
+			<WorldRecordLen<T>>::mutate(game, route, |len| *len += 1);
+			reads += 1;
+			writes += 1;
+		}
+
+		for (game, user, route, _) in <UserData<T>>::iter_keys()
+		{
+			<UserRecordLen<T>>::mutate((game, user, route), |len| *len += 1);
+			reads += 1;
+			writes += 1;
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(reads, writes + 1)
+	}
+}
+
+/// v4: register every game that has an authority with `InternalExternal` access in the new
+/// `Games` registry, owned by one of those authorities.
+///
+/// Games whose authorities all have `External` access are left unregistered, since making one of
+/// them the owner would hand the game to an external writer. `register_game` refuses them, and
+/// root registers them with `force_register_game`. Metadata starts out empty and the creation
+/// block is the block of the upgrade.
+pub mod v4 {
+	use super::*;
+
+	pub fn migrate<T: Config>() -> Weight
+	{
+		let mut reads : Weight = 0;
+		let mut owners : BTreeMap<T::GameID, T::AccountId> = BTreeMap::new();
+
+		for (who, permissions) in old_authorities::<T>()
+		{
+			reads += 1;
+
+			for (game, access) in permissions
+			{
+				if access == OldAccess::InternalExternal
+				{
+					owners.entry(game).or_insert_with(|| who.clone());
+				}
+			}
+		}
+
+		let created_at = <frame_system::Pallet<T>>::block_number();
+		let writes = owners.len() as Weight;
+
+		for (game, owner) in owners
+		{
+			let info = GameInfo::<T> {
+				owner,
+				name: Default::default(),
+				description: Default::default(),
+				website: Default::default(),
+				icon: None,
+				genres: Default::default(),
+				created_at,
+			};
+
+			<Games<T>>::insert(game, info);
+		}
+
+		StorageVersion::new(4).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(reads + 1, writes + 1)
+	}
+}
+
+/// v5: move `AuthoritiesMap` into the `Authorities` double map and its reverse index.
+///
+/// Access levels are carried over as they are; v6 turns them into roles.
+pub mod v5 {
+	use super::*;
+
+	pub fn migrate<T: Config>() -> Weight
+	{
+		let mut reads : Weight = 0;
+		let mut writes : Weight = 0;
+
+		for (who, permissions) in old_authorities::<T>()
+		{
+			reads += 1;
+
+			for (game, access) in permissions
+			{
+				unhashed::put(&<Authorities<T>>::hashed_key_for(game, &who), &access);
+				<AuthorityGames<T>>::insert(&who, game, ());
+				writes += 2;
+			}
+		}
+
+		remove_storage_prefix(<Pallet<T>>::name().as_bytes(), OLD_AUTHORITIES_MAP, &[]);
+		StorageVersion::new(5).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(reads, writes + 2)
+	}
+}
+
+/// v6: replace access levels with roles.
+///
+/// Each game's registered owner becomes its `Owner`, other `InternalExternal` authorities, who could
+/// already manage the team, become `Admin`s, and `External` authorities become `ExternalWriter`s.
+pub mod v6 {
+	use super::*;
+
+	pub fn migrate<T: Config>() -> Weight
+	{
+		let mut reads : Weight = 0;
+		let mut writes : Weight = 0;
+
+		<Authorities<T>>::translate(|game, who, access : OldAccess| {
+			reads += 2;
+			writes += 1;
+
+			let is_owner = <Games<T>>::get(game).map_or(false, |info| info.owner == who);
+
+			Some(match access {
+				_ if is_owner => Role::Owner,
+				OldAccess::InternalExternal => Role::Admin,
+				OldAccess::External => Role::ExternalWriter,
+			})
+		});
+
+		// Owners who had removed themselves get their seat back.
+		for (game, info) in <Games<T>>::iter()
+		{
+			reads += 2;
+
+			if <Authorities<T>>::get(game, &info.owner) != Some(Role::Owner)
+			{
+				grant_access::<T>(game, &info.owner, Role::Owner);
+				writes += 2;
+			}
+		}
+
+		StorageVersion::new(6).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(reads, writes + 1)
+	}
+}
+
+/// v7: give every world and user entry a revision.
+///
+/// Existing entries start at revision 1, as if they had just been created.
+pub mod v7 {
+	use super::*;
+
+	pub fn migrate<T: Config>() -> Weight
+	{
+		let revisioned = |value : BoundedValue<T>| RevisionedValue { value, revision: 1 };
+
+		let count = translate_raw::<T, WorldData<T>, _, _>(revisioned)
+			+ translate_raw::<T, UserData<T>, _, _>(revisioned);
+
+		StorageVersion::new(7).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count, count + 1)
+	}
+}
+
+/// v8: record who last wrote every world and user entry, and when.
+///
+/// Nobody knows who wrote the existing entries, so their writer is left unknown. Their block is
+/// the block of the upgrade and their timestamp the previous block's.
+pub mod v8 {
+	use super::*;
+
+	pub fn migrate<T: Config>() -> Weight
+	{
+		let block = <frame_system::Pallet<T>>::block_number();
+		let timestamp = <pallet_timestamp::Pallet<T>>::get();
+
+		let migrated = |old : RevisionedValue<BoundedValue<T>>| -> StoredEntry<T> {
+			Entry {
+				value: old.value,
+				revision: old.revision,
+				writer: None,
+				block,
+				timestamp,
+			}
+		};
+
+		let count = translate_raw::<T, WorldData<T>, _, _>(migrated)
+			+ translate_raw::<T, UserData<T>, _, _>(migrated);
+
+		StorageVersion::new(8).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(count + 2, count + 1)
+	}
+}
@@ -18,6 +18,7 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
 	}
 );
@@ -25,13 +26,44 @@ frame_support::construct_runtime!(
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 	pub const SS58Prefix: u8 = 42;
+	pub const MaxKeyLen: u32 = 32;
+	pub const MaxValueLen: u32 = 64;
+	pub const MaxExternalEntries: u32 = 16;
+	pub const MaxInternalEntries: u32 = 8;
+	pub const MaxNameLen: u32 = 16;
+	pub const MaxDescriptionLen: u32 = 64;
+	pub const MaxWebsiteLen: u32 = 32;
+	pub const MaxGenres: u32 = 2;
+	pub const MaxGenreLen: u32 = 8;
+	pub const MaxBatchOps: u32 = 4;
+	pub const MaxEventValueLen: u32 = 32;
+	pub const MaxHistoryDepth: u32 = 4;
+	pub const MaxHistoryRemovals: u32 = 4;
+	pub const MaxChangeLogLen: u32 = 8;
+	pub const MinimumPeriod: u64 = 1;
+	pub const MaxExpiriesPerBlock: u32 = 4;
+	pub const MaxExpiries: u32 = 4;
+	// Fits three expiries at `RocksDbWeight`.
+	pub const ExpiryWeightBudget: u64 = 5_000_000_000;
+	pub const MaxSchedules: u32 = 4;
+	pub const MaxSchedulesPerBlock: u32 = 4;
+	pub const TimedScheduleSlot: u64 = 10;
+	pub const MaxTimedSlotsPerBlock: u32 = 4;
+	pub const MaxScheduleDelay: u32 = 2;
+	pub const MaxLeaderboards: u32 = 2;
+	pub const MaxLeaderboardSize: u32 = 4;
+	pub const MaxTriggers: u32 = 4;
+	pub const MaxTriggersPerKey: u32 = 2;
+	pub const MaxGameTriggersPerKey: u32 = 1;
+	pub const MaxClockPhases: u32 = 4;
+	pub const MaxPhaseNameLen: u32 = 8;
 }
 
 impl system::Config for Test {
 	type BaseCallFilter = frame_support::traits::Everything;
 	type BlockWeights = ();
 	type BlockLength = ();
-	type DbWeight = ();
+	type DbWeight = frame_support::weights::constants::RocksDbWeight;
 	type Origin = Origin;
 	type Call = Call;
 	type Index = u64;
@@ -53,9 +85,45 @@ impl system::Config for Test {
 	type OnSetCode = ();
 }
 
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
 impl pallet_template::Config for Test {
 	type Event = Event;
 	type GameID = u32;
+	type MaxKeyLen = MaxKeyLen;
+	type MaxValueLen = MaxValueLen;
+	type MaxExternalEntries = MaxExternalEntries;
+	type MaxInternalEntries = MaxInternalEntries;
+	type MaxNameLen = MaxNameLen;
+	type MaxDescriptionLen = MaxDescriptionLen;
+	type MaxWebsiteLen = MaxWebsiteLen;
+	type MaxGenres = MaxGenres;
+	type MaxGenreLen = MaxGenreLen;
+	type MaxBatchOps = MaxBatchOps;
+	type MaxEventValueLen = MaxEventValueLen;
+	type MaxHistoryDepth = MaxHistoryDepth;
+	type MaxHistoryRemovals = MaxHistoryRemovals;
+	type MaxChangeLogLen = MaxChangeLogLen;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxExpiries = MaxExpiries;
+	type MaxSchedules = MaxSchedules;
+	type MaxSchedulesPerBlock = MaxSchedulesPerBlock;
+	type TimedScheduleSlot = TimedScheduleSlot;
+	type MaxTimedSlotsPerBlock = MaxTimedSlotsPerBlock;
+	type MaxScheduleDelay = MaxScheduleDelay;
+	type MaxLeaderboards = MaxLeaderboards;
+	type MaxLeaderboardSize = MaxLeaderboardSize;
+	type MaxTriggers = MaxTriggers;
+	type MaxTriggersPerKey = MaxTriggersPerKey;
+	type MaxGameTriggersPerKey = MaxGameTriggersPerKey;
+	type MaxClockPhases = MaxClockPhases;
+	type MaxPhaseNameLen = MaxPhaseNameLen;
+	type ExpiryWeightBudget = ExpiryWeightBudget;
 }
 
 // Build genesis storage according to the mock runtime.
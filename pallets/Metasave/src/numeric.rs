@@ -0,0 +1,224 @@
+//! Reading and updating integers stored in entry values.
+//!
+//! Numbers are stored as fixed-width little-endian bytes, the same layout `to_le_bytes` produces.
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::{cmp::Ordering, prelude::*};
+
+/// The width and signedness of an integer entry value.
+#[derive(Encode, Decode, RuntimeDebug, Clone, Copy, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+pub enum IntKind {
+	U8,
+	U16,
+	U32,
+	U64,
+	U128,
+	I8,
+	I16,
+	I32,
+	I64,
+	I128,
+}
+
+impl IntKind {
+	/// Number of bytes a value of this kind takes.
+	pub fn width(&self) -> usize
+	{
+		match self {
+			IntKind::U8 | IntKind::I8 => 1,
+			IntKind::U16 | IntKind::I16 => 2,
+			IntKind::U32 | IntKind::I32 => 4,
+			IntKind::U64 | IntKind::I64 => 8,
+			IntKind::U128 | IntKind::I128 => 16,
+		}
+	}
+
+	pub fn is_signed(&self) -> bool
+	{
+		matches!(self, IntKind::I8 | IntKind::I16 | IntKind::I32 | IntKind::I64 | IntKind::I128)
+	}
+
+	/// Read a value of this kind, widened to 128 bits. `None` if `bytes` is not exactly
+	/// `width()` long.
+	pub fn decode(&self, bytes : &[u8]) -> Option<Number>
+	{
+		if bytes.len() != self.width()
+		{
+			return None;
+		}
+
+		// Sign-extend signed values, zero-extend unsigned ones.
+		let negative = self.is_signed() && bytes[bytes.len() - 1] & 0x80 != 0;
+		let mut wide = if negative { [0xffu8; 16] } else { [0u8; 16] };
+		wide[..bytes.len()].copy_from_slice(bytes);
+
+		Some(if self.is_signed() {
+			Number::Signed(i128::from_le_bytes(wide))
+		} else {
+			Number::Unsigned(u128::from_le_bytes(wide))
+		})
+	}
+}
+
+/// An integer entry value widened to 128 bits.
+///
+/// The derived ordering only makes sense between numbers of the same signedness, i.e. read as the
+/// same `IntKind`.
+#[derive(Encode, Decode, RuntimeDebug, Clone, Copy, TypeInfo, PartialEq, Eq, PartialOrd, Ord, MaxEncodedLen)]
+pub enum Number {
+	Signed(i128),
+	Unsigned(u128),
+}
+
+impl Number {
+	/// Compare against a signed bound, without losing unsigned values above `i128::MAX`.
+	pub fn cmp_i128(&self, other : i128) -> Ordering
+	{
+		match *self {
+			Number::Signed(n) => n.cmp(&other),
+			Number::Unsigned(_) if other < 0 => Ordering::Greater,
+			Number::Unsigned(n) => n.cmp(&(other as u128)),
+		}
+	}
+}
+
+/// What to do when the result of an operation doesn't fit the entry's integer kind.
+#[derive(Encode, Decode, RuntimeDebug, Clone, Copy, TypeInfo, PartialEq, Eq)]
+pub enum OverflowPolicy {
+	/// Fail the operation.
+	Checked,
+	/// Clamp the result to the kind's minimum or maximum.
+	Saturating,
+	/// Wrap around at the kind's boundary.
+	Wrapping,
+}
+
+/// An arithmetic operation applied to an integer entry, with the operand as its right-hand side.
+#[derive(Encode, Decode, RuntimeDebug, Clone, Copy, TypeInfo, PartialEq, Eq)]
+pub enum NumericOp {
+	Add,
+	Sub,
+	Mul,
+	/// Keep the smaller of the entry and the operand.
+	Min,
+	/// Keep the larger of the entry and the operand.
+	Max,
+	/// Store the operand only if it is greater than the entry, e.g. a high score.
+	SetIfGreater,
+	/// Store the operand only if it is less than the entry, e.g. a best lap time.
+	SetIfLess,
+	/// Clamp the entry to a range. The operand is the lower bound followed by the upper bound.
+	Clamp,
+}
+
+impl NumericOp {
+	/// Number of integers of the entry's kind the operand holds.
+	pub fn operand_count(&self) -> usize
+	{
+		match self {
+			NumericOp::Clamp => 2,
+			_ => 1,
+		}
+	}
+}
+
+/// Why an operation could not be applied.
+#[derive(RuntimeDebug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericError {
+	/// The current value or the operand is not as wide as the integer kind.
+	BadSize,
+	/// A clamp range whose lower bound is above its upper bound.
+	InvalidRange,
+	/// The result does not fit the integer kind.
+	Overflow,
+}
+
+/// Apply `op` to the little-endian integer `current`, returning the new value in the same layout.
+pub fn apply(
+	kind : IntKind,
+	op : NumericOp,
+	policy : OverflowPolicy,
+	current : &[u8],
+	operand : &[u8],
+) -> Result<Vec<u8>, NumericError>
+{
+	macro_rules! apply_as {
+		($t:ty) => {{
+			let width = kind.width();
+			if operand.len() != width * op.operand_count()
+			{
+				return Err(NumericError::BadSize);
+			}
+
+			let current = <$t>::from_le_bytes(current.try_into().map_err(|_| NumericError::BadSize)?);
+			let upper = operand.get(width..).map(|b| <$t>::from_le_bytes(b.try_into().unwrap_or_default()));
+			let operand = <$t>::from_le_bytes(operand[..width].try_into().map_err(|_| NumericError::BadSize)?);
+
+			let result = match (op, policy) {
+				(NumericOp::Add, OverflowPolicy::Checked) => current.checked_add(operand),
+				(NumericOp::Add, OverflowPolicy::Saturating) => Some(current.saturating_add(operand)),
+				(NumericOp::Add, OverflowPolicy::Wrapping) => Some(current.wrapping_add(operand)),
+				(NumericOp::Sub, OverflowPolicy::Checked) => current.checked_sub(operand),
+				(NumericOp::Sub, OverflowPolicy::Saturating) => Some(current.saturating_sub(operand)),
+				(NumericOp::Sub, OverflowPolicy::Wrapping) => Some(current.wrapping_sub(operand)),
+				(NumericOp::Mul, OverflowPolicy::Checked) => current.checked_mul(operand),
+				(NumericOp::Mul, OverflowPolicy::Saturating) => Some(current.saturating_mul(operand)),
+				(NumericOp::Mul, OverflowPolicy::Wrapping) => Some(current.wrapping_mul(operand)),
+				(NumericOp::Min, _) => Some(current.min(operand)),
+				(NumericOp::Max, _) => Some(current.max(operand)),
+				(NumericOp::SetIfGreater, _) => Some(if operand > current { operand } else { current }),
+				(NumericOp::SetIfLess, _) => Some(if operand < current { operand } else { current }),
+				(NumericOp::Clamp, _) => {
+					let upper = upper.ok_or(NumericError::BadSize)?;
+					if operand > upper
+					{
+						return Err(NumericError::InvalidRange);
+					}
+					Some(current.clamp(operand, upper))
+				},
+			};
+
+			Ok(result.ok_or(NumericError::Overflow)?.to_le_bytes().to_vec())
+		}};
+	}
+
+	match kind {
+		IntKind::U8 => apply_as!(u8),
+		IntKind::U16 => apply_as!(u16),
+		IntKind::U32 => apply_as!(u32),
+		IntKind::U64 => apply_as!(u64),
+		IntKind::U128 => apply_as!(u128),
+		IntKind::I8 => apply_as!(i8),
+		IntKind::I16 => apply_as!(i16),
+		IntKind::I32 => apply_as!(i32),
+		IntKind::I64 => apply_as!(i64),
+		IntKind::I128 => apply_as!(i128),
+	}
+}
+
+/// A bound an integer entry can cross, e.g. a score reaching a target.
+#[derive(Encode, Decode, RuntimeDebug, Clone, Copy, TypeInfo, PartialEq, Eq, MaxEncodedLen)]
+pub enum Threshold {
+	/// Met while the value is above the bound.
+	Above(i128),
+	/// Met while the value is below the bound.
+	Below(i128),
+}
+
+impl Threshold {
+	pub fn is_met(&self, value : Number) -> bool
+	{
+		match *self {
+			Threshold::Above(bound) => value.cmp_i128(bound) == Ordering::Greater,
+			Threshold::Below(bound) => value.cmp_i128(bound) == Ordering::Less,
+		}
+	}
+
+	/// Whether a change from `old` to `new` crosses the bound. An absent old value doesn't meet it.
+	pub fn crossed(&self, old : Option<Number>, new : Number) -> bool
+	{
+		self.is_met(new) && !old.map_or(false, |old| self.is_met(old))
+	}
+}
@@ -1,5 +1,10 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{mock::*, bits::{self, BitOp}, clock::WorldTime, numeric::{self, IntKind, Number, NumericError, NumericOp, OverflowPolicy, Threshold}, BatchOp, Change, ChangeOp, EntryFlags, Error, EventValue, RankOrder, Role, Route, ScheduleTime, ValueChange, ValueKind, ValueSchema};
+use crate::migrations::{migrate, OldAccess};
+use crate::pallet::{Authorities, AuthorityGames, ChangeLog, ExpiryCount, ExpiryQueue, GamePlayers, Games, HistoryClearing, TimedSchedules, WorldRecordLen};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, storage::migration::put_storage_value, traits::{OnInitialize, PalletInfoAccess, StorageVersion}, StorageHasher, Twox64Concat};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
 
 // #[test]
 // fn it_works_for_default_value() {
@@ -18,3 +23,1745 @@ use frame_support::{assert_noop, assert_ok};
 // 		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::NoneValue);
 // 	});
 // }
+
+const GAME : u32 = 1;
+const OWNER : u64 = 1;
+
+/// Advance to block `n`, running the pallet's `on_initialize` for every block on the way.
+fn run_to_block(n : u64)
+{
+	while System::block_number() < n
+	{
+		System::set_block_number(System::block_number() + 1);
+		TemplateModule::on_initialize(System::block_number());
+	}
+}
+
+/// Register `GAME` with `OWNER` as its owner and give its `key` world entry `value`.
+fn setup_game(key : &[u8], value : &[u8])
+{
+	System::set_block_number(1);
+	assert_ok!(TemplateModule::register_game(Origin::signed(OWNER), GAME));
+	assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (key.to_vec(), value.to_vec()), Route::Internal, None, None));
+}
+
+fn world_value(key : &[u8]) -> Option<Vec<u8>>
+{
+	TemplateModule::world_entry_info(GAME, Route::Internal, key.to_vec()).map(|entry| entry.value)
+}
+
+fn set_locked(key : &[u8], locked : bool)
+{
+	let flags = EntryFlags { write_once: false, locked };
+	assert_ok!(TemplateModule::world_set_entry_flags(Origin::signed(OWNER), GAME, Route::Internal, key.to_vec(), flags));
+}
+
+#[test]
+fn scheduled_change_applies_and_reverts()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::schedule_world_change(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), b"rain".to_vec()), ScheduleTime::Block(3), Some(ScheduleTime::Block(5)), None));
+
+		run_to_block(2);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+
+		run_to_block(3);
+		assert_eq!(world_value(b"weather"), Some(b"rain".to_vec()));
+
+		run_to_block(5);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+		assert_eq!(TemplateModule::scheduled_changes(GAME).count(), 0);
+	});
+}
+
+#[test]
+fn scheduled_change_repeats()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::schedule_world_change(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), b"rain".to_vec()), ScheduleTime::Block(3), Some(ScheduleTime::Block(4)), Some(5)));
+
+		run_to_block(3);
+		assert_eq!(world_value(b"weather"), Some(b"rain".to_vec()));
+
+		run_to_block(4);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+
+		run_to_block(8);
+		assert_eq!(world_value(b"weather"), Some(b"rain".to_vec()));
+
+		run_to_block(9);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+		assert_eq!(TemplateModule::scheduled_changes(GAME).count(), 1);
+	});
+}
+
+#[test]
+fn failed_repeating_change_keeps_its_window()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::schedule_world_change(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), b"rain".to_vec()), ScheduleTime::Block(3), Some(ScheduleTime::Block(5)), Some(10)));
+		set_locked(b"weather", true);
+
+		run_to_block(3);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+
+		set_locked(b"weather", false);
+
+		run_to_block(13);
+		assert_eq!(world_value(b"weather"), Some(b"rain".to_vec()));
+
+		run_to_block(14);
+		assert_eq!(world_value(b"weather"), Some(b"rain".to_vec()));
+
+		run_to_block(15);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+		assert_eq!(TemplateModule::scheduled_changes(GAME).count(), 1);
+	});
+}
+
+#[test]
+fn timestamp_change_fires_once_due()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::schedule_world_change(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), b"rain".to_vec()), ScheduleTime::Timestamp(25), None, None));
+
+		Timestamp::set_timestamp(24);
+		run_to_block(2);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+
+		Timestamp::set_timestamp(25);
+		run_to_block(3);
+		assert_eq!(world_value(b"weather"), Some(b"rain".to_vec()));
+		assert_eq!(TemplateModule::scheduled_changes(GAME).count(), 0);
+	});
+}
+
+#[test]
+fn cancelled_change_leaves_the_queue()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::schedule_world_change(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), b"rain".to_vec()), ScheduleTime::Timestamp(25), None, None));
+		assert_ok!(TemplateModule::cancel_scheduled_change(Origin::signed(OWNER), GAME, 0));
+
+		assert!(!TimedSchedules::<Test>::contains_key(2));
+		assert_noop!(TemplateModule::cancel_scheduled_change(Origin::signed(OWNER), GAME, 0), Error::<Test>::NotFound);
+
+		Timestamp::set_timestamp(30);
+		run_to_block(2);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+	});
+}
+
+#[test]
+fn schedule_must_start_in_the_future()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_noop!(
+			TemplateModule::schedule_world_change(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), b"rain".to_vec()), ScheduleTime::Block(1), None, None),
+			Error::<Test>::InvalidSchedule
+		);
+	});
+}
+
+#[test]
+fn scheduled_change_fails_once_its_creator_is_removed()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 2, Role::InternalWriter));
+		assert_ok!(TemplateModule::schedule_world_change(Origin::signed(2), GAME, Route::Internal, (b"weather".to_vec(), b"rain".to_vec()), ScheduleTime::Block(3), None, None));
+		assert_ok!(TemplateModule::remove_authority(Origin::signed(OWNER), GAME, 2));
+
+		run_to_block(3);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+		assert_eq!(TemplateModule::scheduled_changes(GAME).count(), 0);
+	});
+}
+
+#[test]
+fn leaderboard_never_ranks_below_a_dropped_player()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::set_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), IntKind::U8, RankOrder::Descending, 3));
+
+		for (player, score) in [(10, 10u8), (11, 9), (12, 8), (13, 7), (10, 1)]
+		{
+			assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, player, Route::Internal, (b"score".to_vec(), vec![score]), None, None));
+		}
+
+		assert_eq!(board(), ranking(&[(11, 9), (12, 8)]));
+
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 13, Route::Internal, (b"score".to_vec(), vec![7]), None, None));
+		assert_eq!(board(), ranking(&[(11, 9), (12, 8), (13, 7)]));
+	});
+}
+
+/// Write a value of a storage map the migrations replaced, under `suffix`.
+fn put_old<V : Encode>(map : &[u8], suffix : &[u8], value : V)
+{
+	put_storage_value(TemplateModule::name().as_bytes(), map, suffix, value);
+}
+
+/// The `Twox64Concat` key suffix of `parts`, one hash per part.
+fn old_key(parts : &[Vec<u8>]) -> Vec<u8>
+{
+	parts.iter().flat_map(|part| Twox64Concat::hash(part)).collect()
+}
+
+#[test]
+fn migrates_from_the_first_layout()
+{
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		let world = vec![
+			(b"weather".to_vec(), b"sun".to_vec()),
+			(vec![0; 33], b"long key".to_vec()),
+			(b"huge".to_vec(), vec![0; 65]),
+		];
+		put_old(b"WorldDataMap", &old_key(&[GAME.encode(), Route::Internal.encode()]), world);
+
+		let user = vec![(b"level".to_vec(), vec![3]), (b"huge".to_vec(), vec![0; 65])];
+		put_old(b"UserDataMap", &old_key(&[(GAME, 7u64).encode(), Route::External.encode()]), user);
+		put_old(b"UserDataMap", &old_key(&[(GAME, 8u64).encode(), Route::Internal.encode()]), vec![(b"huge".to_vec(), vec![0; 65])]);
+
+		put_old(b"AuthoritiesMap", &old_key(&[5u64.encode()]), vec![(GAME, OldAccess::InternalExternal)]);
+		put_old(b"AuthoritiesMap", &old_key(&[6u64.encode()]), vec![(GAME, OldAccess::External), (2, OldAccess::External)]);
+
+		migrate::<Test>();
+
+		assert_eq!(StorageVersion::get::<TemplateModule>(), 8);
+
+		let entry = TemplateModule::world_entry_info(GAME, Route::Internal, b"weather".to_vec()).unwrap();
+		assert_eq!((entry.value, entry.revision, entry.writer), (b"sun".to_vec(), 1, None));
+		assert_eq!(TemplateModule::world_entry_info(GAME, Route::Internal, b"huge".to_vec()), None);
+		assert_eq!(WorldRecordLen::<Test>::get(GAME, Route::Internal), 1);
+
+		let entry = TemplateModule::user_entry_info(GAME, &7, Route::External, b"level".to_vec()).unwrap();
+		assert_eq!((entry.value, entry.revision, entry.writer), (vec![3], 1, None));
+		assert_eq!(TemplateModule::user_entry_info(GAME, &7, Route::External, b"huge".to_vec()), None);
+
+		// Players only count the entries that survived.
+		assert_eq!(GamePlayers::<Test>::get(GAME, 7), 1);
+		assert!(!GamePlayers::<Test>::contains_key(GAME, 8));
+		assert_eq!(TemplateModule::games_of(&8).count(), 0);
+
+		assert_eq!(Games::<Test>::get(GAME).map(|info| info.owner), Some(5));
+		assert_eq!(Authorities::<Test>::get(GAME, 5), Some(Role::Owner));
+		assert_eq!(Authorities::<Test>::get(GAME, 6), Some(Role::ExternalWriter));
+
+		// A game with only external writers is left for root to register.
+		assert!(Games::<Test>::get(2).is_none());
+		assert_eq!(Authorities::<Test>::get(2, 6), Some(Role::ExternalWriter));
+	});
+}
+
+#[test]
+fn stale_revision_is_rejected()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"rain".to_vec()), Route::Internal, Some(1), None));
+
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"snow".to_vec()), Route::Internal, Some(1), None),
+			Error::<Test>::StaleRevision
+		);
+		assert_noop!(
+			TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), vec![]), Route::Internal, Some(1)),
+			Error::<Test>::StaleRevision
+		);
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"wind".to_vec(), b"calm".to_vec()), Route::Internal, Some(1), None),
+			Error::<Test>::StaleRevision
+		);
+
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), vec![]), Route::Internal, Some(2)));
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"wind".to_vec(), b"calm".to_vec()), Route::Internal, Some(0), None));
+	});
+}
+
+#[test]
+fn recreated_entry_carries_on_from_its_revision()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"rain".to_vec()), Route::Internal, None, None));
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), vec![]), Route::Internal, None));
+
+		// A writer that last saw revision 1 doesn't overwrite the recreated entry.
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"snow".to_vec()), Route::Internal, Some(0), None),
+			Error::<Test>::StaleRevision
+		);
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"snow".to_vec()), Route::Internal, Some(2), None));
+		assert_eq!(TemplateModule::world_entry_info(GAME, Route::Internal, b"weather".to_vec()).map(|entry| entry.revision), Some(3));
+
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, (b"level".to_vec(), vec![1]), None, None));
+		assert_ok!(TemplateModule::user_remove_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, b"level".to_vec(), Some(1)));
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, (b"level".to_vec(), vec![2]), None, None));
+		assert_eq!(TemplateModule::user_entry_info(GAME, &7, Route::Internal, b"level".to_vec()).map(|entry| entry.revision), Some(2));
+
+		TemplateModule::clear_player_data(GAME, &7);
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, (b"level".to_vec(), vec![3]), Some(2), None));
+		assert_eq!(TemplateModule::user_entry_info(GAME, &7, Route::Internal, b"level".to_vec()).map(|entry| entry.revision), Some(3));
+	});
+}
+
+fn world_revisions(key : &[u8]) -> Vec<u32>
+{
+	TemplateModule::world_history(GAME, Route::Internal, key.to_vec()).into_iter().map(|entry| entry.revision).collect()
+}
+
+#[test]
+fn history_keeps_the_last_values_and_rolls_back()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 2));
+		assert_noop!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 5), Error::<Test>::HistoryTooDeep);
+
+		for value in [b"rain", b"snow", b"hail"]
+		{
+			assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), value.to_vec()), Route::Internal, None, None));
+		}
+		assert_eq!(world_revisions(b"weather"), vec![3, 4]);
+
+		assert_ok!(TemplateModule::world_rollback(Origin::signed(OWNER), GAME, Route::Internal, b"weather".to_vec(), 3));
+		assert_eq!(world_value(b"weather"), Some(b"snow".to_vec()));
+		assert_eq!(world_revisions(b"weather"), vec![4, 5]);
+		assert_noop!(
+			TemplateModule::world_rollback(Origin::signed(OWNER), GAME, Route::Internal, b"weather".to_vec(), 3),
+			Error::<Test>::RevisionNotInHistory
+		);
+
+		// A removed and recreated entry doesn't record a revision twice.
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), vec![]), Route::Internal, None));
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"fog".to_vec()), Route::Internal, None, None));
+		assert_eq!(world_revisions(b"weather"), vec![5, 6]);
+
+		assert_ok!(TemplateModule::world_rollback(Origin::signed(OWNER), GAME, Route::Internal, b"weather".to_vec(), 5));
+		assert_eq!(world_value(b"weather"), Some(b"snow".to_vec()));
+	});
+}
+
+#[test]
+fn history_is_cleared_over_several_calls()
+{
+	let mut ext = new_test_ext();
+
+	ext.execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 1));
+
+		for key in [b"k1", b"k2", b"k3", b"k4", b"k5"]
+		{
+			assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (key.to_vec(), b"v".to_vec()), Route::Internal, None, None));
+		}
+	});
+
+	// Removal limits only apply to committed storage.
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		assert_ok!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 0));
+		assert!(HistoryClearing::<Test>::contains_key(GAME));
+		assert_noop!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 2), Error::<Test>::HistoryClearing);
+	});
+
+	ext.commit_all().unwrap();
+
+	ext.execute_with(|| {
+		assert_ok!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 0));
+		assert!(!HistoryClearing::<Test>::contains_key(GAME));
+		assert_eq!(world_revisions(b"k5"), Vec::<u32>::new());
+		assert_ok!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 2));
+	});
+}
+
+#[test]
+fn repeat_falling_in_a_full_block_is_pushed_back()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::schedule_world_change(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), b"rain".to_vec()), ScheduleTime::Block(3), None, Some(5)));
+
+		// Another game fills block 8, where the change repeats.
+		assert_ok!(TemplateModule::register_game(Origin::signed(2), 2));
+		for _ in 0..4
+		{
+			assert_ok!(TemplateModule::schedule_world_change(Origin::signed(2), 2, Route::Internal, (b"k".to_vec(), b"v".to_vec()), ScheduleTime::Block(8), None, None));
+		}
+		assert_noop!(
+			TemplateModule::schedule_world_change(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), b"snow".to_vec()), ScheduleTime::Block(8), None, None),
+			Error::<Test>::ScheduleQueueFull
+		);
+
+		run_to_block(3);
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"sun".to_vec()), Route::Internal, None, None));
+
+		let (_, schedule) = TemplateModule::scheduled_changes(GAME).next().unwrap();
+		assert_eq!(schedule.start, ScheduleTime::Block(9));
+
+		run_to_block(8);
+		assert_eq!(world_value(b"weather"), Some(b"sun".to_vec()));
+
+		run_to_block(9);
+		assert_eq!(world_value(b"weather"), Some(b"rain".to_vec()));
+	});
+}
+
+fn set_expiring(key : &[u8], expires_at : Option<u64>)
+{
+	assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (key.to_vec(), b"on".to_vec()), Route::Internal, None, expires_at));
+}
+
+#[test]
+fn entry_expires_at_its_block()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_expiring(b"buff", Some(3));
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"buff".to_vec(), b"on".to_vec()), Route::Internal, None, Some(1)),
+			Error::<Test>::ExpiryInPast
+		);
+
+		run_to_block(2);
+		assert_eq!(world_value(b"buff"), Some(b"on".to_vec()));
+
+		run_to_block(3);
+		assert_eq!(world_value(b"buff"), None);
+		assert_eq!(WorldRecordLen::<Test>::get(GAME, Route::Internal), 1);
+		assert_eq!(ExpiryCount::<Test>::get(GAME), 0);
+	});
+}
+
+#[test]
+fn changed_expiry_leaves_the_queue()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+
+		for _ in 0..8
+		{
+			set_expiring(b"buff", Some(5));
+			set_expiring(b"buff", None);
+		}
+		set_expiring(b"buff", Some(5));
+		set_expiring(b"buff", Some(6));
+
+		assert!(!ExpiryQueue::<Test>::contains_key(5));
+		assert_eq!(ExpiryQueue::<Test>::get(6).len(), 1);
+		assert_eq!(ExpiryCount::<Test>::get(GAME), 1);
+
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"buff".to_vec(), vec![]), Route::Internal, None));
+		assert!(!ExpiryQueue::<Test>::contains_key(6));
+		assert_eq!(ExpiryCount::<Test>::get(GAME), 0);
+	});
+}
+
+#[test]
+fn expiries_are_capped_per_game()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+
+		for (key, at) in [(b"k1", 3), (b"k2", 4), (b"k3", 5), (b"k4", 6)]
+		{
+			set_expiring(key, Some(at));
+		}
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"k5".to_vec(), b"on".to_vec()), Route::Internal, None, Some(7)),
+			Error::<Test>::TooManyExpiries
+		);
+
+		// Another game still has room, and a game's count goes down as its entries expire.
+		assert_ok!(TemplateModule::register_game(Origin::signed(2), 2));
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(2), 2, (b"k1".to_vec(), b"on".to_vec()), Route::Internal, None, Some(3)));
+
+		run_to_block(3);
+		set_expiring(b"k5", Some(7));
+	});
+}
+
+#[test]
+fn expiries_over_the_budget_carry_over()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+
+		for key in [b"k1", b"k2", b"k3", b"k4"]
+		{
+			set_expiring(key, Some(3));
+		}
+
+		run_to_block(3);
+		assert_eq!(ExpiryQueue::<Test>::get(3).len(), 1);
+		assert_eq!(world_value(b"k4"), Some(b"on".to_vec()));
+
+		run_to_block(4);
+		assert!(!ExpiryQueue::<Test>::contains_key(3));
+		assert_eq!(world_value(b"k4"), None);
+		assert_eq!(WorldRecordLen::<Test>::get(GAME, Route::Internal), 1);
+	});
+}
+
+#[test]
+fn entry_locked_after_its_expiry_was_set_is_kept()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_expiring(b"buff", Some(3));
+		set_locked(b"buff", true);
+
+		run_to_block(3);
+		assert_eq!(world_value(b"buff"), Some(b"on".to_vec()));
+		assert_eq!(ExpiryCount::<Test>::get(GAME), 0);
+
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"buff".to_vec(), b"on".to_vec()), Route::Internal, None, Some(5)),
+			Error::<Test>::EntryLocked
+		);
+
+		// The expiry was dropped with the entry kept, so unlocking it doesn't expire it later.
+		set_locked(b"buff", false);
+		run_to_block(5);
+		assert_eq!(world_value(b"buff"), Some(b"on".to_vec()));
+	});
+}
+
+fn change_seqs(since : u64) -> Vec<u64>
+{
+	TemplateModule::changes_since(GAME, since).into_iter().map(|change| change.seq).collect()
+}
+
+#[test]
+fn changes_since_lists_later_changes()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::External, (b"level".to_vec(), vec![1]), None, None));
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), vec![]), Route::Internal, None));
+
+		let changes = TemplateModule::changes_since(GAME, 0);
+		let summary : Vec<_> = changes.iter().map(|change| (change.seq, change.route, change.user, &change.key[..], change.op)).collect();
+		assert_eq!(summary, vec![
+			(1, Route::Internal, None, &b"weather"[..], ChangeOp::Write),
+			(2, Route::External, Some(7), &b"level"[..], ChangeOp::Write),
+			(3, Route::Internal, None, &b"weather"[..], ChangeOp::Remove),
+		]);
+		assert_eq!(changes[0].block, 1);
+
+		assert_eq!(change_seqs(2), vec![3]);
+		assert_eq!(change_seqs(3), Vec::<u64>::new());
+		assert_eq!(TemplateModule::changes_since(2, 0), vec![]);
+	});
+}
+
+#[test]
+fn change_log_keeps_the_last_changes()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+
+		for day in 0..10u8
+		{
+			assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), vec![day]), Route::Internal, None, None));
+		}
+
+		// A caller that last saw change 2 can tell it missed changes 3 and 4.
+		assert_eq!(change_seqs(2), (4..=11).collect::<Vec<u64>>());
+		assert_eq!(change_seqs(9), vec![10, 11]);
+		assert!(!ChangeLog::<Test>::contains_key(GAME, 3));
+	});
+}
+
+fn set_score(player : u64, score : u8)
+{
+	assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, player, Route::Internal, (b"score".to_vec(), vec![score]), None, None));
+}
+
+fn board() -> Option<Vec<(u64, Number)>>
+{
+	TemplateModule::leaderboard(GAME, Route::Internal, b"score".to_vec())
+}
+
+/// A ranking of `U8` scores, as `board` returns it.
+fn ranking(scores : &[(u64, u128)]) -> Option<Vec<(u64, Number)>>
+{
+	Some(scores.iter().map(|&(player, score)| (player, Number::Unsigned(score))).collect())
+}
+
+#[test]
+fn ascending_leaderboard_ranks_lowest_first()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::set_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), IntKind::U8, RankOrder::Ascending, 3));
+
+		for (player, score) in [(10, 5), (11, 3), (12, 9), (13, 4)]
+		{
+			set_score(player, score);
+		}
+		assert_eq!(board(), ranking(&[(11, 3), (13, 4), (10, 5)]));
+
+		assert_ok!(TemplateModule::user_remove_data_record(Origin::signed(OWNER), GAME, 11, Route::Internal, b"score".to_vec(), None));
+		assert_eq!(board(), ranking(&[(13, 4), (10, 5)]));
+
+		// A player scoring worse than the player left off isn't ranked.
+		set_score(14, 10);
+		assert_eq!(board(), ranking(&[(13, 4), (10, 5)]));
+	});
+}
+
+#[test]
+fn resized_leaderboard_keeps_the_best()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::set_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), IntKind::U8, RankOrder::Descending, 3));
+		assert_noop!(
+			TemplateModule::set_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), IntKind::U8, RankOrder::Descending, 5),
+			Error::<Test>::InvalidLeaderboardSize
+		);
+
+		for (player, score) in [(10, 5), (11, 3), (12, 9)]
+		{
+			set_score(player, score);
+		}
+
+		assert_ok!(TemplateModule::set_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), IntKind::U8, RankOrder::Descending, 2));
+		assert_eq!(board(), ranking(&[(12, 9), (10, 5)]));
+
+		// Growing the board again doesn't bring back the player it let go of.
+		assert_ok!(TemplateModule::set_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), IntKind::U8, RankOrder::Descending, 3));
+		assert_eq!(board(), ranking(&[(12, 9), (10, 5)]));
+
+		// Another order starts the board over.
+		assert_ok!(TemplateModule::set_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), IntKind::U8, RankOrder::Ascending, 3));
+		assert_eq!(board(), ranking(&[]));
+	});
+}
+
+#[test]
+fn reseeded_leaderboard_ranks_current_scores()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+
+		for (player, score) in [(10, 5), (11, 3), (12, 9)]
+		{
+			set_score(player, score);
+		}
+
+		assert_noop!(
+			TemplateModule::reseed_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), vec![10]),
+			Error::<Test>::NotFound
+		);
+
+		assert_ok!(TemplateModule::set_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), IntKind::U8, RankOrder::Descending, 2));
+		assert_eq!(board(), ranking(&[]));
+
+		assert_noop!(
+			TemplateModule::reseed_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), vec![10, 11, 12]),
+			Error::<Test>::TooManyPlayers
+		);
+		assert_ok!(TemplateModule::reseed_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), vec![10, 12]));
+		assert_eq!(board(), ranking(&[(12, 9), (10, 5)]));
+
+		set_score(13, 7);
+		assert_eq!(board(), ranking(&[(12, 9), (13, 7)]));
+
+		// Players scoring below player 10, whom the board let go of, are left off.
+		set_score(13, 1);
+		set_score(11, 3);
+		assert_eq!(board(), ranking(&[(12, 9)]));
+
+		// Reseeding forgets the cutoff.
+		assert_ok!(TemplateModule::reseed_leaderboard(Origin::signed(OWNER), GAME, Route::Internal, b"score".to_vec(), vec![12, 11]));
+		assert_eq!(board(), ranking(&[(12, 9), (11, 3)]));
+	});
+}
+
+const WATCHED : u32 = 2;
+const WATCHED_OWNER : u64 = 2;
+
+/// Register `WATCHED` and give its external `score` entry `score`.
+fn set_watched(score : i32)
+{
+	if !Games::<Test>::contains_key(WATCHED)
+	{
+		assert_ok!(TemplateModule::register_game(Origin::signed(WATCHED_OWNER), WATCHED));
+	}
+
+	assert_ok!(TemplateModule::world_update_data_record(Origin::signed(WATCHED_OWNER), WATCHED, (b"score".to_vec(), score.to_le_bytes().to_vec()), Route::External, None, None));
+}
+
+/// Have `who` register a rule writing `GAME`'s internal `unlocked` entry once `WATCHED`'s score
+/// goes above 10.
+fn register_unlock(who : u64)
+{
+	assert_ok!(TemplateModule::register_trigger(Origin::signed(who), GAME, WATCHED, b"score".to_vec(), IntKind::I32, Threshold::Above(10), Route::Internal, (b"unlocked".to_vec(), b"yes".to_vec())));
+}
+
+fn has_event(event : crate::Event<Test>) -> bool
+{
+	System::events().iter().any(|record| record.event == Event::TemplateModule(event.clone()))
+}
+
+#[test]
+fn trigger_fires_when_its_threshold_is_crossed()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_watched(0);
+		register_unlock(OWNER);
+
+		set_watched(5);
+		assert_eq!(world_value(b"unlocked"), None);
+
+		set_watched(11);
+		assert_eq!(world_value(b"unlocked"), Some(b"yes".to_vec()));
+
+		// The rule fires on the write that crosses the threshold, not while it stays crossed.
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"unlocked".to_vec(), b"no".to_vec()), Route::Internal, None, None));
+		set_watched(12);
+		assert_eq!(world_value(b"unlocked"), Some(b"no".to_vec()));
+
+		set_watched(3);
+		set_watched(20);
+		assert_eq!(world_value(b"unlocked"), Some(b"yes".to_vec()));
+
+		assert_ok!(TemplateModule::remove_trigger(Origin::signed(OWNER), GAME, 0));
+		assert_noop!(TemplateModule::remove_trigger(Origin::signed(OWNER), GAME, 0), Error::<Test>::NotFound);
+
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"unlocked".to_vec(), b"no".to_vec()), Route::Internal, None, None));
+		set_watched(3);
+		set_watched(30);
+		assert_eq!(world_value(b"unlocked"), Some(b"no".to_vec()));
+	});
+}
+
+#[test]
+fn triggers_watching_an_entry_are_capped()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_noop!(
+			TemplateModule::register_trigger(Origin::signed(OWNER), GAME, WATCHED, b"score".to_vec(), IntKind::I32, Threshold::Above(10), Route::Internal, (b"unlocked".to_vec(), b"yes".to_vec())),
+			Error::<Test>::UnknownGame
+		);
+
+		set_watched(0);
+		register_unlock(OWNER);
+		assert_noop!(
+			TemplateModule::register_trigger(Origin::signed(OWNER), GAME, WATCHED, b"score".to_vec(), IntKind::I32, Threshold::Below(0), Route::Internal, (b"lost".to_vec(), b"yes".to_vec())),
+			Error::<Test>::TooManyTriggers
+		);
+		assert_ok!(TemplateModule::register_trigger(Origin::signed(OWNER), GAME, WATCHED, b"level".to_vec(), IntKind::I32, Threshold::Below(0), Route::Internal, (b"lost".to_vec(), b"yes".to_vec())));
+
+		// Other games can still watch the entry, up to `MaxTriggersPerKey` rules in all.
+		assert_ok!(TemplateModule::register_game(Origin::signed(3), 3));
+		assert_ok!(TemplateModule::register_game(Origin::signed(4), 4));
+		assert_ok!(TemplateModule::register_trigger(Origin::signed(3), 3, WATCHED, b"score".to_vec(), IntKind::I32, Threshold::Above(10), Route::Internal, (b"k".to_vec(), b"v".to_vec())));
+		assert_noop!(
+			TemplateModule::register_trigger(Origin::signed(4), 4, WATCHED, b"score".to_vec(), IntKind::I32, Threshold::Above(10), Route::Internal, (b"k".to_vec(), b"v".to_vec())),
+			Error::<Test>::TooManyTriggers
+		);
+	});
+}
+
+#[test]
+fn trigger_of_a_removed_creator_fails()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_watched(0);
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 5, Role::InternalWriter));
+		register_unlock(5);
+		assert_ok!(TemplateModule::remove_authority(Origin::signed(OWNER), GAME, 5));
+
+		set_watched(11);
+		assert_eq!(world_value(b"unlocked"), None);
+		assert!(has_event(crate::Event::TriggerFailed(GAME, 0, Error::<Test>::InvalidAuthority.into())));
+
+		// The watched write itself went through.
+		assert_eq!(TemplateModule::world_entry_info(WATCHED, Route::External, b"score".to_vec()).map(|entry| entry.value), Some(11i32.to_le_bytes().to_vec()));
+	});
+}
+
+#[test]
+fn trigger_writes_do_not_fire_further_rules()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_watched(0);
+
+		// `GAME` raises its external level when the score crosses 10, and `WATCHED` notes when
+		// that level crosses 50.
+		assert_ok!(TemplateModule::register_trigger(Origin::signed(OWNER), GAME, WATCHED, b"score".to_vec(), IntKind::I32, Threshold::Above(10), Route::External, (b"level".to_vec(), 100i32.to_le_bytes().to_vec())));
+		assert_ok!(TemplateModule::register_trigger(Origin::signed(WATCHED_OWNER), WATCHED, GAME, b"level".to_vec(), IntKind::I32, Threshold::Above(50), Route::Internal, (b"noted".to_vec(), b"yes".to_vec())));
+
+		set_watched(11);
+		assert_eq!(TemplateModule::world_entry_info(GAME, Route::External, b"level".to_vec()).map(|entry| entry.value), Some(100i32.to_le_bytes().to_vec()));
+		assert!(TemplateModule::world_entry_info(WATCHED, Route::Internal, b"noted".to_vec()).is_none());
+
+		// A write by an authority does fire the rule.
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"level".to_vec(), 0i32.to_le_bytes().to_vec()), Route::External, None, None));
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"level".to_vec(), 60i32.to_le_bytes().to_vec()), Route::External, None, None));
+		assert_eq!(TemplateModule::world_entry_info(WATCHED, Route::Internal, b"noted".to_vec()).map(|entry| entry.value), Some(b"yes".to_vec()));
+	});
+}
+
+#[test]
+fn watched_games_managers_can_remove_triggers()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_watched(0);
+		register_unlock(OWNER);
+		assert_ok!(TemplateModule::add_authority(Origin::signed(WATCHED_OWNER), WATCHED, 6, Role::ExternalWriter));
+
+		assert_noop!(TemplateModule::remove_trigger(Origin::signed(3), GAME, 0), Error::<Test>::InvalidAccess);
+		assert_noop!(TemplateModule::remove_trigger(Origin::signed(6), GAME, 0), Error::<Test>::InvalidAccess);
+		assert_ok!(TemplateModule::remove_trigger(Origin::signed(WATCHED_OWNER), GAME, 0));
+
+		set_watched(11);
+		assert_eq!(world_value(b"unlocked"), None);
+	});
+}
+
+#[test]
+fn admin_cannot_manage_owner_or_admins()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 2, Role::Admin));
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 3, Role::Admin));
+
+		assert_noop!(TemplateModule::add_authority(Origin::signed(2), GAME, 4, Role::Admin), Error::<Test>::InvalidAccess);
+		assert_noop!(TemplateModule::remove_authority(Origin::signed(2), GAME, 3), Error::<Test>::InvalidAccess);
+		assert_noop!(TemplateModule::set_authority_role(Origin::signed(2), GAME, 3, Role::Moderator), Error::<Test>::InvalidAccess);
+		assert_noop!(TemplateModule::set_authority_role(Origin::signed(2), GAME, OWNER, Role::Admin), Error::<Test>::OwnerRequired);
+		assert_noop!(TemplateModule::remove_authority(Origin::signed(2), GAME, OWNER), Error::<Test>::OwnerRequired);
+
+		// Below their own rank admins manage freely.
+		assert_ok!(TemplateModule::add_authority(Origin::signed(2), GAME, 4, Role::ExternalWriter));
+		assert_ok!(TemplateModule::set_authority_role(Origin::signed(2), GAME, 4, Role::InternalWriter));
+		assert_noop!(TemplateModule::set_authority_role(Origin::signed(2), GAME, 4, Role::Admin), Error::<Test>::InvalidAccess);
+		assert_ok!(TemplateModule::remove_authority(Origin::signed(2), GAME, 4));
+
+		// The owner manages admins, and an admin may still step down.
+		assert_ok!(TemplateModule::set_authority_role(Origin::signed(OWNER), GAME, 3, Role::Moderator));
+		assert_ok!(TemplateModule::remove_authority(Origin::signed(2), GAME, 2));
+		assert_eq!(Authorities::<Test>::get(GAME, 2), None);
+	});
+}
+
+#[test]
+fn owner_cannot_be_removed()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_noop!(TemplateModule::remove_authority(Origin::signed(OWNER), GAME, OWNER), Error::<Test>::OwnerRequired);
+		assert_noop!(TemplateModule::set_authority_role(Origin::signed(OWNER), GAME, OWNER, Role::Admin), Error::<Test>::OwnerRequired);
+		assert_noop!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 2, Role::Owner), Error::<Test>::InvalidAccess);
+	});
+}
+
+#[test]
+fn moderator_removes_but_cannot_write()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 4, Role::Moderator));
+
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(4), GAME, (b"weather".to_vec(), b"rain".to_vec()), Route::Internal, None, None),
+			Error::<Test>::InvalidAccess
+		);
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(4), GAME, (b"score".to_vec(), b"1".to_vec()), Route::External, None, None),
+			Error::<Test>::InvalidAccess
+		);
+		assert_noop!(TemplateModule::add_authority(Origin::signed(4), GAME, 5, Role::ExternalWriter), Error::<Test>::InvalidAccess);
+
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(4), GAME, (b"weather".to_vec(), vec![]), Route::Internal, None));
+		assert_eq!(world_value(b"weather"), None);
+	});
+}
+
+#[test]
+fn accepting_ownership_keeps_the_previous_owner_as_admin()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_noop!(TemplateModule::accept_ownership(Origin::signed(2), GAME), Error::<Test>::NotPendingOwner);
+
+		assert_ok!(TemplateModule::transfer_ownership(Origin::signed(OWNER), GAME, 2));
+		assert_noop!(TemplateModule::accept_ownership(Origin::signed(3), GAME), Error::<Test>::NotPendingOwner);
+		assert_ok!(TemplateModule::accept_ownership(Origin::signed(2), GAME));
+
+		assert_eq!(Games::<Test>::get(GAME).map(|info| info.owner), Some(2));
+		assert_eq!(Authorities::<Test>::get(GAME, 2), Some(Role::Owner));
+		assert_eq!(Authorities::<Test>::get(GAME, OWNER), Some(Role::Admin));
+
+		// The former owner is now outranked by the new one.
+		assert_noop!(TemplateModule::remove_authority(Origin::signed(OWNER), GAME, 2), Error::<Test>::OwnerRequired);
+		assert_ok!(TemplateModule::remove_authority(Origin::signed(2), GAME, OWNER));
+		assert_noop!(TemplateModule::accept_ownership(Origin::signed(2), GAME), Error::<Test>::NotPendingOwner);
+	});
+}
+
+/// Assert that every call writing or removing the internal world entry `key` fails with
+/// `EntryLocked`.
+fn assert_entry_locked(key : &[u8])
+{
+	let key = key.to_vec();
+	let one = 1i32.to_le_bytes().to_vec();
+
+	assert_noop!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (key.clone(), one.clone()), Route::Internal, None, None), Error::<Test>::EntryLocked);
+	assert_noop!(TemplateModule::world_mod_data_record(Origin::signed(OWNER), GAME, (key.clone(), one.clone()), Route::Internal, None), Error::<Test>::EntryLocked);
+	assert_noop!(
+		TemplateModule::world_numeric_op(Origin::signed(OWNER), GAME, Route::Internal, key.clone(), IntKind::I32, NumericOp::Max, 100i32.to_le_bytes().to_vec(), OverflowPolicy::Checked, None),
+		Error::<Test>::EntryLocked
+	);
+	assert_noop!(TemplateModule::world_bit_op(Origin::signed(OWNER), GAME, Route::Internal, key.clone(), BitOp::Set(30), None), Error::<Test>::EntryLocked);
+	assert_noop!(TemplateModule::world_compare_and_swap(Origin::signed(OWNER), GAME, Route::Internal, (key.clone(), one.clone()), world_value(&key), None), Error::<Test>::EntryLocked);
+	assert_noop!(TemplateModule::world_rollback(Origin::signed(OWNER), GAME, Route::Internal, key.clone(), 1), Error::<Test>::EntryLocked);
+	assert_noop!(
+		TemplateModule::batch(Origin::signed(OWNER), GAME, vec![BatchOp::WorldUpdate { route: Route::Internal, entry: (key.clone(), one), expected_revision: None, expires_at: None }]),
+		Error::<Test>::EntryLocked
+	);
+	assert_noop!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (key, vec![]), Route::Internal, None), Error::<Test>::EntryLocked);
+}
+
+#[test]
+fn locked_entry_rejects_every_change()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 4));
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"count".to_vec(), 1i32.to_le_bytes().to_vec()), Route::Internal, None, None));
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"count".to_vec(), 2i32.to_le_bytes().to_vec()), Route::Internal, None, None));
+
+		set_locked(b"count", true);
+		assert_entry_locked(b"count");
+
+		set_locked(b"count", false);
+		assert_ok!(TemplateModule::world_rollback(Origin::signed(OWNER), GAME, Route::Internal, b"count".to_vec(), 1));
+		assert_eq!(world_value(b"count"), Some(1i32.to_le_bytes().to_vec()));
+	});
+}
+
+#[test]
+fn write_once_entry_is_written_once()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 4));
+
+		let write_once = EntryFlags { write_once: true, locked: false };
+		assert_ok!(TemplateModule::world_set_entry_flags(Origin::signed(OWNER), GAME, Route::Internal, b"badge".to_vec(), write_once));
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"badge".to_vec(), 7i32.to_le_bytes().to_vec()), Route::Internal, None, None));
+
+		assert_entry_locked(b"badge");
+		assert_eq!(world_value(b"badge"), Some(7i32.to_le_bytes().to_vec()));
+
+		// The flag can't be taken back, not even along with locking the entry.
+		assert_noop!(
+			TemplateModule::world_set_entry_flags(Origin::signed(OWNER), GAME, Route::Internal, b"badge".to_vec(), EntryFlags::default()),
+			Error::<Test>::EntryLocked
+		);
+		assert_noop!(
+			TemplateModule::world_set_entry_flags(Origin::signed(OWNER), GAME, Route::Internal, b"badge".to_vec(), EntryFlags { write_once: false, locked: true }),
+			Error::<Test>::EntryLocked
+		);
+	});
+}
+
+#[test]
+fn locked_user_entry_rejects_changes()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, (b"level".to_vec(), 1i32.to_le_bytes().to_vec()), None, None));
+		assert_ok!(TemplateModule::user_set_entry_flags(Origin::signed(OWNER), GAME, 7, Route::Internal, b"level".to_vec(), EntryFlags { write_once: false, locked: true }));
+
+		assert_noop!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, (b"level".to_vec(), 2i32.to_le_bytes().to_vec()), None, None), Error::<Test>::EntryLocked);
+		assert_noop!(
+			TemplateModule::batch(Origin::signed(OWNER), GAME, vec![BatchOp::UserRemove { user: 7, route: Route::Internal, key: b"level".to_vec(), expected_revision: None }]),
+			Error::<Test>::EntryLocked
+		);
+		assert_noop!(TemplateModule::user_remove_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, b"level".to_vec(), None), Error::<Test>::EntryLocked);
+	});
+}
+
+fn world_update_op(route : Route, key : &[u8], value : &[u8]) -> BatchOp<u64, u64>
+{
+	BatchOp::WorldUpdate { route, entry: (key.to_vec(), value.to_vec()), expected_revision: None, expires_at: None }
+}
+
+#[test]
+fn failed_batch_op_rolls_back_the_earlier_ones()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		let events = System::events().len();
+
+		let ops = vec![
+			world_update_op(Route::Internal, b"door", b"open"),
+			BatchOp::UserUpdate { user: 7, route: Route::External, entry: (b"level".to_vec(), b"2".to_vec()), expected_revision: None, expires_at: None },
+			BatchOp::WorldRemove { route: Route::Internal, key: b"missing".to_vec(), expected_revision: None },
+		];
+		assert_noop!(TemplateModule::batch(Origin::signed(OWNER), GAME, ops), Error::<Test>::NotFound);
+
+		assert_eq!(world_value(b"door"), None);
+		assert!(TemplateModule::user_entry_info(GAME, &7, Route::External, b"level".to_vec()).is_none());
+		assert_eq!(System::events().len(), events);
+
+		let ops = vec![
+			world_update_op(Route::Internal, b"door", b"open"),
+			BatchOp::WorldRemove { route: Route::Internal, key: b"weather".to_vec(), expected_revision: None },
+		];
+		assert_ok!(TemplateModule::batch(Origin::signed(OWNER), GAME, ops));
+
+		assert_eq!(world_value(b"door"), Some(b"open".to_vec()));
+		assert_eq!(world_value(b"weather"), None);
+		assert!(has_event(crate::Event::BatchApplied(GAME, OWNER, 2)));
+	});
+}
+
+#[test]
+fn batch_needs_access_to_every_op()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 5, Role::ExternalWriter));
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 6, Role::Moderator));
+
+		let ops = vec![world_update_op(Route::External, b"score", b"1"), world_update_op(Route::Internal, b"door", b"open")];
+		assert_noop!(TemplateModule::batch(Origin::signed(5), GAME, ops), Error::<Test>::InvalidAccess);
+
+		let ops = vec![
+			BatchOp::WorldRemove { route: Route::Internal, key: b"weather".to_vec(), expected_revision: None },
+			world_update_op(Route::Internal, b"weather", b"rain"),
+		];
+		assert_noop!(TemplateModule::batch(Origin::signed(6), GAME, ops), Error::<Test>::InvalidAccess);
+		assert_noop!(TemplateModule::batch(Origin::signed(9), GAME, vec![world_update_op(Route::External, b"score", b"1")]), Error::<Test>::InvalidAuthority);
+
+		assert_noop!(TemplateModule::batch(Origin::signed(OWNER), GAME, vec![]), Error::<Test>::BadBatchSize);
+		let ops = (0..5).map(|i| world_update_op(Route::Internal, &[i], b"x")).collect();
+		assert_noop!(TemplateModule::batch(Origin::signed(OWNER), GAME, ops), Error::<Test>::BadBatchSize);
+	});
+}
+
+fn apply_u8(op : NumericOp, policy : OverflowPolicy, current : u8, operand : u8) -> Result<u8, NumericError>
+{
+	numeric::apply(IntKind::U8, op, policy, &[current], &[operand]).map(|value| value[0])
+}
+
+fn apply_i16(op : NumericOp, policy : OverflowPolicy, current : i16, operand : &[i16]) -> Result<i16, NumericError>
+{
+	let operand : Vec<u8> = operand.iter().flat_map(|n| n.to_le_bytes().to_vec()).collect();
+	numeric::apply(IntKind::I16, op, policy, &current.to_le_bytes(), &operand).map(|value| i16::from_le_bytes([value[0], value[1]]))
+}
+
+#[test]
+fn overflow_policies_apply_at_the_limits()
+{
+	use OverflowPolicy::*;
+
+	assert_eq!(apply_u8(NumericOp::Add, Checked, 250, 5), Ok(255));
+	assert_eq!(apply_u8(NumericOp::Add, Checked, 250, 6), Err(NumericError::Overflow));
+	assert_eq!(apply_u8(NumericOp::Add, Saturating, 250, 6), Ok(u8::MAX));
+	assert_eq!(apply_u8(NumericOp::Add, Wrapping, 250, 6), Ok(0));
+	assert_eq!(apply_u8(NumericOp::Sub, Checked, 0, 1), Err(NumericError::Overflow));
+	assert_eq!(apply_u8(NumericOp::Sub, Saturating, 0, 1), Ok(0));
+	assert_eq!(apply_u8(NumericOp::Sub, Wrapping, 0, 1), Ok(u8::MAX));
+
+	assert_eq!(apply_i16(NumericOp::Add, Checked, i16::MAX, &[1]), Err(NumericError::Overflow));
+	assert_eq!(apply_i16(NumericOp::Add, Saturating, i16::MAX, &[1]), Ok(i16::MAX));
+	assert_eq!(apply_i16(NumericOp::Add, Wrapping, i16::MAX, &[1]), Ok(i16::MIN));
+	assert_eq!(apply_i16(NumericOp::Sub, Checked, i16::MIN, &[1]), Err(NumericError::Overflow));
+	assert_eq!(apply_i16(NumericOp::Sub, Saturating, i16::MIN, &[1]), Ok(i16::MIN));
+	assert_eq!(apply_i16(NumericOp::Sub, Wrapping, i16::MIN, &[1]), Ok(i16::MAX));
+	assert_eq!(apply_i16(NumericOp::Add, Checked, -5, &[3]), Ok(-2));
+
+	// Wider values, which `world_mod_data_record` works on.
+	let max = i32::MAX.to_le_bytes();
+	assert_eq!(numeric::apply(IntKind::I32, NumericOp::Add, Checked, &max, &1i32.to_le_bytes()), Err(NumericError::Overflow));
+	let max = u128::MAX.to_le_bytes();
+	assert_eq!(numeric::apply(IntKind::U128, NumericOp::Add, Wrapping, &max, &1u128.to_le_bytes()), Ok(0u128.to_le_bytes().to_vec()));
+}
+
+#[test]
+fn values_not_as_wide_as_their_kind_are_rejected()
+{
+	let apply = |current : &[u8], operand : &[u8]| numeric::apply(IntKind::U16, NumericOp::Add, OverflowPolicy::Checked, current, operand);
+
+	assert_eq!(apply(&[1, 0], &[1, 0]), Ok(vec![2, 0]));
+	assert_eq!(apply(&[1], &[1, 0]), Err(NumericError::BadSize));
+	assert_eq!(apply(&[1, 0, 0], &[1, 0]), Err(NumericError::BadSize));
+	assert_eq!(apply(&[1, 0], &[1]), Err(NumericError::BadSize));
+	assert_eq!(apply(&[1, 0], &[1, 0, 2, 0]), Err(NumericError::BadSize));
+
+	// A clamp takes exactly two integers.
+	assert_eq!(numeric::apply(IntKind::U16, NumericOp::Clamp, OverflowPolicy::Checked, &[1, 0], &[1, 0]), Err(NumericError::BadSize));
+}
+
+#[test]
+fn numeric_op_overflow_fails_the_write()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"count", &i32::MAX.to_le_bytes());
+
+		assert_noop!(
+			TemplateModule::world_mod_data_record(Origin::signed(OWNER), GAME, (b"count".to_vec(), 1i32.to_le_bytes().to_vec()), Route::Internal, None),
+			Error::<Test>::Overflow
+		);
+		assert_noop!(
+			TemplateModule::world_mod_data_record(Origin::signed(OWNER), GAME, (b"count".to_vec(), vec![1]), Route::Internal, None),
+			Error::<Test>::BadSize
+		);
+
+		assert_ok!(TemplateModule::world_numeric_op(Origin::signed(OWNER), GAME, Route::Internal, b"count".to_vec(), IntKind::I32, NumericOp::Add, 1i32.to_le_bytes().to_vec(), OverflowPolicy::Wrapping, None));
+		assert_eq!(world_value(b"count"), Some(i32::MIN.to_le_bytes().to_vec()));
+	});
+}
+
+#[test]
+fn comparing_ops_keep_the_better_value()
+{
+	use OverflowPolicy::Checked;
+
+	assert_eq!(apply_u8(NumericOp::SetIfGreater, Checked, 10, 12), Ok(12));
+	assert_eq!(apply_u8(NumericOp::SetIfGreater, Checked, 10, 8), Ok(10));
+	assert_eq!(apply_u8(NumericOp::SetIfLess, Checked, 10, 8), Ok(8));
+	assert_eq!(apply_u8(NumericOp::SetIfLess, Checked, 10, 12), Ok(10));
+	assert_eq!(apply_i16(NumericOp::SetIfGreater, Checked, -3, &[-5]), Ok(-3));
+	assert_eq!(apply_i16(NumericOp::SetIfLess, Checked, -3, &[-5]), Ok(-5));
+
+	assert_eq!(apply_u8(NumericOp::Min, Checked, 10, 3), Ok(3));
+	assert_eq!(apply_u8(NumericOp::Max, Checked, 10, 3), Ok(10));
+	assert_eq!(apply_i16(NumericOp::Min, Checked, -1, &[1]), Ok(-1));
+	assert_eq!(apply_i16(NumericOp::Max, Checked, -1, &[1]), Ok(1));
+}
+
+#[test]
+fn clamp_keeps_values_in_range()
+{
+	use OverflowPolicy::Checked;
+
+	assert_eq!(apply_i16(NumericOp::Clamp, Checked, 50, &[-10, 10]), Ok(10));
+	assert_eq!(apply_i16(NumericOp::Clamp, Checked, -50, &[-10, 10]), Ok(-10));
+	assert_eq!(apply_i16(NumericOp::Clamp, Checked, 3, &[-10, 10]), Ok(3));
+	assert_eq!(apply_i16(NumericOp::Clamp, Checked, 3, &[7, 7]), Ok(7));
+	assert_eq!(apply_i16(NumericOp::Clamp, Checked, 3, &[10, -10]), Err(NumericError::InvalidRange));
+}
+
+#[test]
+fn mul_follows_the_overflow_policy()
+{
+	use OverflowPolicy::*;
+
+	assert_eq!(apply_u8(NumericOp::Mul, Checked, 15, 17), Ok(255));
+	assert_eq!(apply_u8(NumericOp::Mul, Checked, 16, 16), Err(NumericError::Overflow));
+	assert_eq!(apply_u8(NumericOp::Mul, Saturating, 16, 16), Ok(u8::MAX));
+	assert_eq!(apply_u8(NumericOp::Mul, Wrapping, 16, 17), Ok(16));
+	assert_eq!(apply_i16(NumericOp::Mul, Checked, -300, &[-3]), Ok(900));
+	assert_eq!(apply_i16(NumericOp::Mul, Saturating, i16::MIN, &[-1]), Ok(i16::MAX));
+	assert_eq!(apply_i16(NumericOp::Mul, Saturating, i16::MIN, &[2]), Ok(i16::MIN));
+}
+
+#[test]
+fn high_score_only_improves()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"best", &100u32.to_le_bytes());
+		let set_if_greater = |score : u32| TemplateModule::world_numeric_op(Origin::signed(OWNER), GAME, Route::Internal, b"best".to_vec(), IntKind::U32, NumericOp::SetIfGreater, score.to_le_bytes().to_vec(), OverflowPolicy::Checked, None);
+
+		assert_ok!(set_if_greater(90));
+		assert_eq!(world_value(b"best"), Some(100u32.to_le_bytes().to_vec()));
+		assert_ok!(set_if_greater(120));
+		assert_eq!(world_value(b"best"), Some(120u32.to_le_bytes().to_vec()));
+
+		let clamp = [0u32.to_le_bytes(), 50u32.to_le_bytes()].concat();
+		assert_ok!(TemplateModule::world_numeric_op(Origin::signed(OWNER), GAME, Route::Internal, b"best".to_vec(), IntKind::U32, NumericOp::Clamp, clamp, OverflowPolicy::Checked, None));
+		assert_eq!(world_value(b"best"), Some(50u32.to_le_bytes().to_vec()));
+
+		let reversed = [50u32.to_le_bytes(), 0u32.to_le_bytes()].concat();
+		assert_noop!(
+			TemplateModule::world_numeric_op(Origin::signed(OWNER), GAME, Route::Internal, b"best".to_vec(), IntKind::U32, NumericOp::Clamp, reversed, OverflowPolicy::Checked, None),
+			Error::<Test>::InvalidRange
+		);
+	});
+}
+
+fn apply_bits(op : BitOp, value : &[u8]) -> Vec<u8>
+{
+	let mut value = value.to_vec();
+	op.apply(&mut value);
+	value
+}
+
+#[test]
+fn bit_ops_grow_the_value_as_needed()
+{
+	assert_eq!(apply_bits(BitOp::Set(0), &[]), vec![0b0000_0001]);
+	assert_eq!(apply_bits(BitOp::Set(9), &[0b0000_0001]), vec![0b0000_0001, 0b0000_0010]);
+	assert_eq!(apply_bits(BitOp::Toggle(17), &[0xff]), vec![0xff, 0, 0b0000_0010]);
+	assert_eq!(apply_bits(BitOp::Toggle(7), &[0xff]), vec![0b0111_1111]);
+
+	// Bits past the end are clear already, so clearing them leaves the value alone.
+	assert_eq!(apply_bits(BitOp::Clear(20), &[0xff]), vec![0xff]);
+	assert_eq!(apply_bits(BitOp::Clear(3), &[0xff]), vec![0b1111_0111]);
+
+	assert_eq!(apply_bits(BitOp::SetMask(vec![0b1010_0000, 0b0000_0001]), &[0b0000_0101]), vec![0b1010_0101, 0b0000_0001]);
+	assert_eq!(apply_bits(BitOp::SetMask(vec![0b0000_0010]), &[0, 0xf0]), vec![0b0000_0010, 0xf0]);
+
+	assert!(bits::test(&[0, 0b0000_0100], 10));
+	assert!(!bits::test(&[0, 0b0000_0100], 11));
+	assert!(!bits::test(&[0xff], 64));
+}
+
+#[test]
+fn bit_past_the_value_limit_is_rejected()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		let bit_op = |op : BitOp| TemplateModule::world_bit_op(Origin::signed(OWNER), GAME, Route::Internal, b"badges".to_vec(), op, None);
+
+		// `MaxValueLen` is 64 bytes, so bit 511 is the last one that fits.
+		assert_ok!(bit_op(BitOp::Set(511)));
+		assert_eq!(world_value(b"badges").map(|value| value.len()), Some(64));
+		assert_eq!(TemplateModule::world_test_bit(GAME, Route::Internal, b"badges".to_vec(), 511), Some(true));
+
+		assert_noop!(bit_op(BitOp::Set(512)), Error::<Test>::ValueTooLarge);
+		assert_noop!(bit_op(BitOp::Toggle(u32::MAX)), Error::<Test>::ValueTooLarge);
+		assert_noop!(bit_op(BitOp::SetMask(vec![1; 65])), Error::<Test>::ValueTooLarge);
+
+		assert_ok!(bit_op(BitOp::Clear(u32::MAX)));
+		assert_ok!(bit_op(BitOp::Clear(511)));
+		assert_eq!(TemplateModule::world_test_bit(GAME, Route::Internal, b"badges".to_vec(), 511), Some(false));
+	});
+}
+
+fn schema(kind : ValueKind, min : Option<i128>, max : Option<i128>) -> ValueSchema<Test>
+{
+	ValueSchema { kind, min, max, default: None }
+}
+
+#[test]
+fn schema_checks_the_layout_of_values()
+{
+	let flag = schema(ValueKind::Bool, None, None);
+	assert!(flag.accepts(&[0]) && flag.accepts(&[1]));
+	assert!(!flag.accepts(&[2]) && !flag.accepts(&[]) && !flag.accepts(&[1, 0]));
+
+	let health = schema(ValueKind::Int(IntKind::I16), Some(-100), Some(100));
+	assert!(health.accepts(&(-100i16).to_le_bytes()) && health.accepts(&100i16.to_le_bytes()));
+	assert!(!health.accepts(&(-101i16).to_le_bytes()) && !health.accepts(&101i16.to_le_bytes()));
+	assert!(!health.accepts(&[5]) && !health.accepts(&5i32.to_le_bytes()));
+
+	// Unsigned values above `i128::MAX` still compare correctly against the bounds.
+	let huge = schema(ValueKind::Int(IntKind::U128), Some(0), Some(i128::MAX));
+	assert!(huge.accepts(&(i128::MAX as u128).to_le_bytes()));
+	assert!(!huge.accepts(&u128::MAX.to_le_bytes()));
+	assert!(schema(ValueKind::Int(IntKind::U128), Some(-1), None).accepts(&u128::MAX.to_le_bytes()));
+
+	let name = schema(ValueKind::String, Some(1), Some(4));
+	assert!(name.accepts("hé".as_bytes()));
+	assert!(!name.accepts(&[0xff, 0xfe]) && !name.accepts(b"") && !name.accepts(b"names"));
+
+	let path = schema(ValueKind::List(IntKind::U16), Some(1), Some(2));
+	assert!(path.accepts(&[1, 0]) && path.accepts(&[1, 0, 2, 0]));
+	assert!(!path.accepts(&[1, 0, 2]) && !path.accepts(&[]) && !path.accepts(&[1, 0, 2, 0, 3, 0]));
+}
+
+#[test]
+fn schema_is_enforced_on_writes()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::world_set_schema(Origin::signed(OWNER), GAME, Route::Internal, b"lives".to_vec(), ValueKind::Int(IntKind::U8), Some(0), Some(9), Some(vec![3])));
+
+		assert_noop!(
+			TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"lives".to_vec(), vec![10]), Route::Internal, None, None),
+			Error::<Test>::SchemaMismatch
+		);
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"lives".to_vec(), vec![9]), Route::Internal, None, None));
+
+		// Numeric ops are held to the schema too.
+		assert_noop!(
+			TemplateModule::world_numeric_op(Origin::signed(OWNER), GAME, Route::Internal, b"lives".to_vec(), IntKind::U8, NumericOp::Add, vec![1], OverflowPolicy::Checked, None),
+			Error::<Test>::SchemaMismatch
+		);
+	});
+}
+
+#[test]
+fn schema_must_accept_its_own_default()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		let set_schema = |kind : ValueKind, min : Option<i128>, max : Option<i128>, default : Option<Vec<u8>>| {
+			TemplateModule::world_set_schema(Origin::signed(OWNER), GAME, Route::Internal, b"lives".to_vec(), kind, min, max, default)
+		};
+
+		assert_noop!(set_schema(ValueKind::Int(IntKind::U8), Some(0), Some(9), Some(vec![10])), Error::<Test>::InvalidSchema);
+		assert_noop!(set_schema(ValueKind::Int(IntKind::U8), None, None, Some(vec![1, 0])), Error::<Test>::InvalidSchema);
+		assert_noop!(set_schema(ValueKind::Bool, None, None, Some(vec![2])), Error::<Test>::InvalidSchema);
+		assert_noop!(set_schema(ValueKind::Int(IntKind::U8), Some(9), Some(0), None), Error::<Test>::InvalidSchema);
+		assert_noop!(set_schema(ValueKind::Fixed { decimals: 19 }, None, None, None), Error::<Test>::InvalidSchema);
+		assert_ok!(set_schema(ValueKind::Fixed { decimals: 18 }, None, None, Some(0i64.to_le_bytes().to_vec())));
+	});
+}
+
+#[test]
+fn absent_entry_can_be_claimed_once()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 5, Role::ExternalWriter));
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 6, Role::ExternalWriter));
+		let claim = |who : u64| TemplateModule::world_compare_and_swap(Origin::signed(who), GAME, Route::External, (b"sword".to_vec(), who.encode()), None, None);
+
+		assert_ok!(claim(5));
+		assert_noop!(claim(6), Error::<Test>::ValueMismatch);
+		assert_eq!(TemplateModule::world_entry_info(GAME, Route::External, b"sword".to_vec()).map(|entry| entry.value), Some(5u64.encode()));
+	});
+}
+
+#[test]
+fn swap_needs_the_current_value()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		let swap = |value : &[u8], expected : Option<&str>| {
+			TemplateModule::world_compare_and_swap(Origin::signed(OWNER), GAME, Route::Internal, (b"weather".to_vec(), value.to_vec()), expected.map(|e| e.as_bytes().to_vec()), None)
+		};
+
+		assert_noop!(swap(b"rain", Some("snow")), Error::<Test>::ValueMismatch);
+		assert_noop!(swap(b"rain", None), Error::<Test>::ValueMismatch);
+		assert_ok!(swap(b"rain", Some("sun")));
+		assert_eq!(world_value(b"weather"), Some(b"rain".to_vec()));
+
+		// Once removed, the entry is only written by expecting it to be absent.
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), vec![]), Route::Internal, None));
+		assert_noop!(swap(b"sun", Some("rain")), Error::<Test>::ValueMismatch);
+		assert_ok!(swap(b"sun", None));
+	});
+}
+
+fn set_user(game : u32, who : u64, user : u64, key : &[u8])
+{
+	assert_ok!(TemplateModule::user_update_data_record(Origin::signed(who), game, user, Route::External, (key.to_vec(), b"1".to_vec()), None, None));
+}
+
+fn remove_user(game : u32, who : u64, user : u64, key : &[u8])
+{
+	assert_ok!(TemplateModule::user_remove_data_record(Origin::signed(who), game, user, Route::External, key.to_vec(), None));
+}
+
+fn sorted<I : Ord>(items : impl Iterator<Item = I>) -> Vec<I>
+{
+	let mut items : Vec<I> = items.collect();
+	items.sort();
+	items
+}
+
+#[test]
+fn players_are_indexed_while_they_hold_entries()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_watched(0);
+
+		set_user(GAME, OWNER, 7, b"level");
+		set_user(GAME, OWNER, 7, b"coins");
+		set_user(GAME, OWNER, 8, b"level");
+		set_user(WATCHED, WATCHED_OWNER, 7, b"level");
+		assert_eq!(GamePlayers::<Test>::get(GAME, 7), 2);
+		assert_eq!(sorted(TemplateModule::players_of(GAME)), vec![7, 8]);
+		assert_eq!(sorted(TemplateModule::games_of(&7)), vec![GAME, WATCHED]);
+
+		// Overwriting an entry doesn't count it again.
+		set_user(GAME, OWNER, 7, b"level");
+		assert_eq!(GamePlayers::<Test>::get(GAME, 7), 2);
+
+		remove_user(GAME, OWNER, 7, b"level");
+		assert_eq!(sorted(TemplateModule::games_of(&7)), vec![GAME, WATCHED]);
+		remove_user(GAME, OWNER, 7, b"coins");
+		assert!(!GamePlayers::<Test>::contains_key(GAME, 7));
+		assert_eq!(sorted(TemplateModule::players_of(GAME)), vec![8]);
+		assert_eq!(sorted(TemplateModule::games_of(&7)), vec![WATCHED]);
+	});
+}
+
+#[test]
+fn cleared_player_leaves_the_indexes()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_watched(0);
+		set_user(GAME, OWNER, 7, b"level");
+		set_user(GAME, OWNER, 7, b"coins");
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, (b"seed".to_vec(), b"42".to_vec()), None, None));
+		set_user(WATCHED, WATCHED_OWNER, 7, b"level");
+
+		assert_eq!(TemplateModule::clear_player_data(GAME, &7), 3);
+		assert!(TemplateModule::user_entry_info(GAME, &7, Route::External, b"level".to_vec()).is_none());
+		assert!(TemplateModule::user_entry_info(GAME, &7, Route::Internal, b"seed".to_vec()).is_none());
+		assert_eq!(TemplateModule::players_of(GAME).count(), 0);
+		assert_eq!(sorted(TemplateModule::games_of(&7)), vec![WATCHED]);
+		assert!(TemplateModule::user_entry_info(WATCHED, &7, Route::External, b"level".to_vec()).is_some());
+
+		assert_eq!(TemplateModule::clear_player_data(GAME, &7), 0);
+
+		// The player can come back, counted from scratch.
+		set_user(GAME, OWNER, 7, b"level");
+		assert_eq!(GamePlayers::<Test>::get(GAME, 7), 1);
+	});
+}
+
+#[test]
+fn registering_a_game_makes_the_caller_its_owner()
+{
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::register_game(Origin::signed(OWNER), GAME));
+		assert_noop!(TemplateModule::register_game(Origin::signed(2), GAME), Error::<Test>::AlreadyRegisteredGame);
+
+		let info = Games::<Test>::get(GAME).unwrap();
+		assert_eq!((info.owner, info.created_at), (OWNER, 3));
+		assert_eq!(Authorities::<Test>::get(GAME, OWNER), Some(Role::Owner));
+		assert!(has_event(crate::Event::GameRegistered(GAME, OWNER)));
+	});
+}
+
+#[test]
+fn game_info_is_set_by_managers_within_bounds()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 2, Role::InternalWriter));
+		let set_info = |who : u64, name : &[u8], genres : Vec<Vec<u8>>| {
+			TemplateModule::set_game_info(Origin::signed(who), GAME, name.to_vec(), b"A game".to_vec(), b"example.org".to_vec(), None, genres)
+		};
+
+		assert_noop!(set_info(2, b"Quest", vec![]), Error::<Test>::InvalidAccess);
+		assert_noop!(set_info(OWNER, &[b'a'; 17], vec![]), Error::<Test>::NameTooLong);
+		assert_noop!(set_info(OWNER, b"Quest", vec![b"adventure".to_vec()]), Error::<Test>::GenreTooLong);
+		assert_noop!(set_info(OWNER, b"Quest", vec![b"rpg".to_vec(), b"puzzle".to_vec(), b"racing".to_vec()]), Error::<Test>::TooManyGenres);
+		assert_noop!(TemplateModule::set_game_info(Origin::signed(OWNER), 9, b"Quest".to_vec(), vec![], vec![], None, vec![]), Error::<Test>::InvalidAuthority);
+
+		assert_ok!(set_info(OWNER, b"Quest", vec![b"rpg".to_vec(), b"puzzle".to_vec()]));
+		let info = Games::<Test>::get(GAME).unwrap();
+		assert_eq!(info.name.into_inner(), b"Quest".to_vec());
+		assert_eq!(info.website.into_inner(), b"example.org".to_vec());
+		assert_eq!(info.genres.len(), 2);
+	});
+}
+
+#[test]
+fn game_with_authorities_is_registered_by_root()
+{
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		// Authorities granted before the game registry existed.
+		Authorities::<Test>::insert(GAME, 5, Role::InternalWriter);
+
+		assert_noop!(TemplateModule::register_game(Origin::signed(OWNER), GAME), Error::<Test>::GameHasAuthorities);
+		assert_noop!(TemplateModule::force_register_game(Origin::signed(OWNER), GAME, 5), sp_runtime::DispatchError::BadOrigin);
+
+		assert_ok!(TemplateModule::force_register_game(Origin::root(), GAME, 5));
+		assert_eq!(Games::<Test>::get(GAME).map(|info| info.owner), Some(5));
+		assert_eq!(Authorities::<Test>::get(GAME, 5), Some(Role::Owner));
+		assert_noop!(TemplateModule::force_register_game(Origin::root(), GAME, 6), Error::<Test>::AlreadyRegisteredGame);
+	});
+}
+
+/// Assert that every authority is indexed under its account, and nothing else is.
+fn assert_authority_index()
+{
+	for (game, who, _) in Authorities::<Test>::iter()
+	{
+		assert!(AuthorityGames::<Test>::contains_key(who, game), "{} is not indexed under {}", game, who);
+	}
+
+	for (who, game, ()) in AuthorityGames::<Test>::iter()
+	{
+		assert!(Authorities::<Test>::contains_key(game, who), "{} is indexed under {} but holds no role", game, who);
+	}
+}
+
+#[test]
+fn authority_index_follows_roles()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_watched(0);
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 5, Role::ExternalWriter));
+		assert_ok!(TemplateModule::add_authority(Origin::signed(WATCHED_OWNER), WATCHED, 5, Role::Moderator));
+		assert_authority_index();
+		assert_eq!(sorted(TemplateModule::authority_games(&5)), vec![GAME, WATCHED]);
+		assert_eq!(sorted(TemplateModule::authorities_of(GAME).map(|(who, _)| who)), vec![OWNER, 5]);
+
+		assert_ok!(TemplateModule::set_authority_role(Origin::signed(OWNER), GAME, 5, Role::InternalWriter));
+		assert_ok!(TemplateModule::remove_authority(Origin::signed(WATCHED_OWNER), WATCHED, 5));
+		assert_authority_index();
+		assert_eq!(sorted(TemplateModule::authority_games(&5)), vec![GAME]);
+		assert_eq!(TemplateModule::role_of(WATCHED, &5), None);
+
+		// Ownership moves to an account that is already an authority, and to one that isn't.
+		assert_ok!(TemplateModule::transfer_ownership(Origin::signed(OWNER), GAME, 5));
+		assert_ok!(TemplateModule::accept_ownership(Origin::signed(5), GAME));
+		assert_ok!(TemplateModule::transfer_ownership(Origin::signed(WATCHED_OWNER), WATCHED, 6));
+		assert_ok!(TemplateModule::accept_ownership(Origin::signed(6), WATCHED));
+		assert_authority_index();
+		assert_eq!(sorted(TemplateModule::authority_games(&6)), vec![WATCHED]);
+		assert_eq!(sorted(TemplateModule::authority_games(&WATCHED_OWNER)), vec![WATCHED]);
+		assert_eq!(TemplateModule::role_of(GAME, &OWNER), Some(Role::Admin));
+
+		assert_ok!(TemplateModule::remove_authority(Origin::signed(5), GAME, OWNER));
+		assert_authority_index();
+		assert_eq!(TemplateModule::authority_games(&OWNER).count(), 0);
+	});
+}
+
+/// The last event the pallet deposited.
+fn last_event() -> crate::Event<Test>
+{
+	System::events().into_iter().rev()
+		.find_map(|record| match record.event {
+			Event::TemplateModule(event) => Some(event),
+			_ => None,
+		})
+		.expect("the pallet deposited an event")
+}
+
+fn change(key : &[u8], old : Option<Vec<u8>>, new : Option<Vec<u8>>, revision : u32) -> Change<Test>
+{
+	ValueChange { key: key.to_vec(), old: old.map(EventValue::Raw), new: new.map(EventValue::Raw), revision }
+}
+
+#[test]
+fn state_changes_emit_events()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		use crate::Event::*;
+
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"rain".to_vec()), Route::Internal, None, None));
+		assert_eq!(last_event(), WorldDataUpdated(GAME, Route::Internal, OWNER, change(b"weather", Some(b"sun".to_vec()), Some(b"rain".to_vec()), 2)));
+
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), vec![]), Route::Internal, None));
+		assert_eq!(last_event(), WorldDataRemoved(GAME, Route::Internal, OWNER, change(b"weather", Some(b"rain".to_vec()), None, 2)));
+
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::External, (b"level".to_vec(), vec![1]), None, None));
+		assert_eq!(last_event(), UserDataUpdated(GAME, 7, Route::External, OWNER, change(b"level", None, Some(vec![1]), 1)));
+
+		assert_ok!(TemplateModule::user_numeric_op(Origin::signed(OWNER), GAME, 7, Route::External, b"level".to_vec(), IntKind::U8, NumericOp::Add, vec![2], OverflowPolicy::Checked, None));
+		assert_eq!(last_event(), UserDataModified(GAME, 7, Route::External, OWNER, change(b"level", Some(vec![1]), Some(vec![3]), 2)));
+
+		assert_ok!(TemplateModule::user_remove_data_record(Origin::signed(OWNER), GAME, 7, Route::External, b"level".to_vec(), None));
+		assert_eq!(last_event(), UserDataRemoved(GAME, 7, Route::External, OWNER, change(b"level", Some(vec![3]), None, 2)));
+
+		assert_ok!(TemplateModule::world_set_schema(Origin::signed(OWNER), GAME, Route::Internal, b"lives".to_vec(), ValueKind::Int(IntKind::U8), None, None, None));
+		assert_eq!(last_event(), WorldSchemaSet(GAME, Route::Internal, OWNER, b"lives".to_vec()));
+
+		set_locked(b"lives", true);
+		assert_eq!(last_event(), WorldEntryFlagsSet(GAME, Route::Internal, OWNER, b"lives".to_vec(), EntryFlags { write_once: false, locked: true }));
+
+		assert_ok!(TemplateModule::set_history_depth(Origin::signed(OWNER), GAME, 2));
+		assert_eq!(last_event(), HistoryDepthSet(GAME, 2, OWNER));
+
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 5, Role::ExternalWriter));
+		assert_eq!(last_event(), AuthorityAdded(GAME, 5, Role::ExternalWriter, OWNER));
+		assert_ok!(TemplateModule::set_authority_role(Origin::signed(OWNER), GAME, 5, Role::Moderator));
+		assert_eq!(last_event(), AuthorityChanged(GAME, 5, Role::Moderator, OWNER));
+		assert_ok!(TemplateModule::remove_authority(Origin::signed(OWNER), GAME, 5));
+		assert_eq!(last_event(), AuthorityRemoved(GAME, 5, OWNER));
+
+		assert_ok!(TemplateModule::transfer_ownership(Origin::signed(OWNER), GAME, 6));
+		assert_eq!(last_event(), OwnershipTransferProposed(GAME, OWNER, 6));
+		assert_ok!(TemplateModule::cancel_ownership_transfer(Origin::signed(OWNER), GAME));
+		assert_eq!(last_event(), OwnershipTransferCancelled(GAME, OWNER));
+	});
+}
+
+/// Indexes of the events deposited under `topic` from event `since` on.
+fn topic_events(topic : H256, since : u32) -> Vec<u32>
+{
+	System::event_topics(&topic).into_iter().map(|(_, index)| index).filter(|index| *index >= since).collect()
+}
+
+#[test]
+fn events_are_indexed_by_game_route_and_key()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		set_watched(0);
+		let since = System::events().len() as u32;
+
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"weather".to_vec(), b"rain".to_vec()), Route::Internal, None, None));
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"score".to_vec(), vec![1]), Route::External, None, None));
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 5, Role::ExternalWriter));
+		set_watched(4);
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(OWNER), GAME, 7, Route::Internal, (b"score".to_vec(), vec![2]), None, None));
+
+		assert_eq!(topic_events(TemplateModule::game_topic(GAME), since), vec![since, since + 1, since + 2, since + 4]);
+		assert_eq!(topic_events(TemplateModule::game_topic(WATCHED), since), vec![since + 3]);
+		assert_eq!(topic_events(TemplateModule::route_topic(GAME, Route::External), since), vec![since + 1]);
+		assert_eq!(topic_events(TemplateModule::route_topic(GAME, Route::Internal), since), vec![since, since + 4]);
+
+		// A key's topic covers both routes and every player, but only within its game.
+		assert_eq!(topic_events(TemplateModule::key_topic(GAME, b"score"), since), vec![since + 1, since + 4]);
+		assert_eq!(topic_events(TemplateModule::key_topic(WATCHED, b"score"), since), vec![since + 3]);
+		assert_eq!(topic_events(TemplateModule::key_topic(GAME, b"weather"), since), vec![since]);
+	});
+}
+
+#[test]
+fn long_values_are_hashed_in_events()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		let write = |value : &[u8]| TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"map".to_vec(), value.to_vec()), Route::Internal, None, None);
+		let hashed = |value : &[u8]| Some(EventValue::Hashed(BlakeTwo256::hash(value)));
+
+		// `MaxEventValueLen` is 32 bytes.
+		let short = [1u8; 32];
+		let long = [2u8; 33];
+		let longer = [3u8; 64];
+
+		assert_ok!(write(&short));
+		assert_eq!(last_event(), crate::Event::WorldDataUpdated(GAME, Route::Internal, OWNER, change(b"map", None, Some(short.to_vec()), 1)));
+
+		assert_ok!(write(&long));
+		let expected = ValueChange { key: b"map".to_vec(), old: Some(EventValue::Raw(short.to_vec())), new: hashed(&long), revision: 2 };
+		assert_eq!(last_event(), crate::Event::WorldDataUpdated(GAME, Route::Internal, OWNER, expected));
+
+		assert_ok!(write(&longer));
+		let expected = ValueChange { key: b"map".to_vec(), old: hashed(&long), new: hashed(&longer), revision: 3 };
+		assert_eq!(last_event(), crate::Event::WorldDataUpdated(GAME, Route::Internal, OWNER, expected));
+
+		assert_ok!(TemplateModule::world_remove_data_record(Origin::signed(OWNER), GAME, (b"map".to_vec(), vec![]), Route::Internal, None));
+		let expected = ValueChange { key: b"map".to_vec(), old: hashed(&longer), new: None, revision: 3 };
+		assert_eq!(last_event(), crate::Event::WorldDataRemoved(GAME, Route::Internal, OWNER, expected));
+	});
+}
+
+#[test]
+fn modified_values_report_old_and_new()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"bits", &[0b0000_0001]);
+
+		assert_ok!(TemplateModule::world_bit_op(Origin::signed(OWNER), GAME, Route::Internal, b"bits".to_vec(), BitOp::Set(9), None));
+		assert_eq!(last_event(), crate::Event::WorldDataModified(GAME, Route::Internal, OWNER, change(b"bits", Some(vec![0b0000_0001]), Some(vec![0b0000_0001, 0b0000_0010]), 2)));
+
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(OWNER), GAME, (b"count".to_vec(), 5i32.to_le_bytes().to_vec()), Route::Internal, None, None));
+		assert_ok!(TemplateModule::world_mod_data_record(Origin::signed(OWNER), GAME, (b"count".to_vec(), (-2i32).to_le_bytes().to_vec()), Route::Internal, None));
+		assert_eq!(last_event(), crate::Event::WorldDataModified(GAME, Route::Internal, OWNER, change(b"count", Some(5i32.to_le_bytes().to_vec()), Some(3i32.to_le_bytes().to_vec()), 2)));
+	});
+}
+
+#[test]
+fn entries_record_their_last_writer_and_block()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_ok!(TemplateModule::add_authority(Origin::signed(OWNER), GAME, 5, Role::ExternalWriter));
+
+		System::set_block_number(4);
+		Timestamp::set_timestamp(24_000);
+		assert_ok!(TemplateModule::world_update_data_record(Origin::signed(5), GAME, (b"score".to_vec(), vec![1]), Route::External, None, None));
+		assert_ok!(TemplateModule::user_update_data_record(Origin::signed(5), GAME, 7, Route::External, (b"level".to_vec(), vec![1]), None, None));
+
+		let entry = TemplateModule::world_entry_info(GAME, Route::External, b"score".to_vec()).unwrap();
+		assert_eq!((entry.writer, entry.block, entry.timestamp, entry.revision), (Some(5), 4, 24_000, 1));
+		let entry = TemplateModule::user_entry_info(GAME, &7, Route::External, b"level".to_vec()).unwrap();
+		assert_eq!((entry.writer, entry.block, entry.timestamp), (Some(5), 4, 24_000));
+
+		// Every kind of write moves the metadata on.
+		System::set_block_number(6);
+		Timestamp::set_timestamp(36_000);
+		assert_ok!(TemplateModule::world_numeric_op(Origin::signed(OWNER), GAME, Route::External, b"score".to_vec(), IntKind::U8, NumericOp::Add, vec![1], OverflowPolicy::Checked, None));
+
+		let entry = TemplateModule::world_entry_info(GAME, Route::External, b"score".to_vec()).unwrap();
+		assert_eq!((entry.writer, entry.block, entry.timestamp, entry.revision), (Some(OWNER), 6, 36_000, 2));
+		assert_eq!(entry.value, vec![2]);
+	});
+}
+
+fn world_time_at(now : u64) -> Option<WorldTime<Vec<u8>>>
+{
+	Timestamp::set_timestamp(now);
+	TemplateModule::world_time(GAME)
+}
+
+fn reading(elapsed : u64, day : u64, time_of_day : u64, phase : &[u8]) -> Option<WorldTime<Vec<u8>>>
+{
+	Some(WorldTime { elapsed, day, time_of_day, phase: Some(phase.to_vec()) })
+}
+
+#[test]
+fn world_clock_follows_the_timestamp()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		assert_eq!(world_time_at(5_000), None);
+
+		// Twice real time, with a day of 1000 in-world milliseconds.
+		let phases = vec![(b"dawn".to_vec(), 200), (b"day".to_vec(), 400), (b"night".to_vec(), 800)];
+		assert_ok!(TemplateModule::set_world_clock(Origin::signed(OWNER), GAME, 10_000, 2_000, 1_000, phases.clone()));
+
+		// Before the epoch the clock reads 0, in the night carried over from the day before.
+		assert_eq!(world_time_at(5_000), reading(0, 0, 0, b"night"));
+		assert_eq!(world_time_at(10_300), reading(600, 0, 600, b"day"));
+		assert_eq!(world_time_at(10_450), reading(900, 0, 900, b"night"));
+		assert_eq!(world_time_at(10_550), reading(1_100, 1, 100, b"night"));
+		assert_eq!(world_time_at(10_600), reading(1_200, 1, 200, b"dawn"));
+
+		// At half speed from timestamp 0.
+		assert_ok!(TemplateModule::set_world_clock(Origin::signed(OWNER), GAME, 0, 500, 1_000, phases));
+		assert_eq!(world_time_at(10_600), reading(5_300, 5, 300, b"dawn"));
+
+		assert_ok!(TemplateModule::set_world_clock(Origin::signed(OWNER), GAME, 0, 1_000, 1_000, vec![]));
+		assert_eq!(world_time_at(2_500), Some(WorldTime { elapsed: 2_500, day: 2, time_of_day: 500, phase: None }));
+	});
+}
+
+#[test]
+fn world_clock_must_be_consistent()
+{
+	new_test_ext().execute_with(|| {
+		setup_game(b"weather", b"sun");
+		let set_clock = |speed : u32, day_length : u64, phases : Vec<(&str, u64)>| {
+			let phases = phases.into_iter().map(|(name, start)| (name.as_bytes().to_vec(), start)).collect();
+			TemplateModule::set_world_clock(Origin::signed(OWNER), GAME, 0, speed, day_length, phases)
+		};
+
+		assert_noop!(set_clock(0, 1_000, vec![]), Error::<Test>::InvalidClock);
+		assert_noop!(set_clock(1_000, 0, vec![]), Error::<Test>::InvalidClock);
+		assert_noop!(set_clock(1_000, 1_000, vec![("night", 1_000)]), Error::<Test>::InvalidClock);
+		assert_noop!(set_clock(1_000, 1_000, vec![("day", 500), ("dawn", 200)]), Error::<Test>::InvalidClock);
+		assert_noop!(set_clock(1_000, 1_000, vec![("dawn", 200), ("dawn", 200)]), Error::<Test>::InvalidClock);
+		assert_noop!(set_clock(1_000, 1_000, vec![("afternoon", 200)]), Error::<Test>::InvalidClock);
+		assert_noop!(set_clock(1_000, 1_000, vec![("a", 0), ("b", 1), ("c", 2), ("d", 3), ("e", 4)]), Error::<Test>::InvalidClock);
+
+		assert_ok!(set_clock(1_000, 1_000, vec![("a", 0), ("b", 1), ("c", 2), ("d", 999)]));
+		assert_ok!(TemplateModule::remove_world_clock(Origin::signed(OWNER), GAME));
+		assert_noop!(TemplateModule::remove_world_clock(Origin::signed(OWNER), GAME), Error::<Test>::NotFound);
+	});
+}
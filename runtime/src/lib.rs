@@ -101,10 +101,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 /// This determines the average expected block time that we are targeting.
@@ -282,10 +282,71 @@ impl pallet_template::Config for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const MetasaveMaxKeyLen: u32 = 128;
+	pub const MetasaveMaxValueLen: u32 = 2048;
+	pub const MetasaveMaxExternalEntries: u32 = 1024;
+	pub const MetasaveMaxInternalEntries: u32 = 1024;
+	pub const MetasaveMaxNameLen: u32 = 64;
+	pub const MetasaveMaxDescriptionLen: u32 = 512;
+	pub const MetasaveMaxWebsiteLen: u32 = 128;
+	pub const MetasaveMaxGenres: u32 = 8;
+	pub const MetasaveMaxGenreLen: u32 = 32;
+	pub const MetasaveMaxBatchOps: u32 = 32;
+	pub const MetasaveMaxEventValueLen: u32 = 256;
+	pub const MetasaveMaxHistoryDepth: u32 = 16;
+	pub const MetasaveMaxHistoryRemovals: u32 = 1024;
+	pub const MetasaveMaxChangeLogLen: u32 = 1024;
+	pub const MetasaveMaxExpiriesPerBlock: u32 = 256;
+	pub const MetasaveMaxExpiries: u32 = 1024;
+	pub const MetasaveMaxSchedules: u32 = 64;
+	pub const MetasaveMaxSchedulesPerBlock: u32 = 32;
+	pub const MetasaveTimedScheduleSlot: u64 = SLOT_DURATION;
+	pub const MetasaveMaxTimedSlotsPerBlock: u32 = 64;
+	pub const MetasaveMaxScheduleDelay: u32 = 16;
+	pub const MetasaveMaxLeaderboards: u32 = 16;
+	pub const MetasaveMaxLeaderboardSize: u32 = 100;
+	pub const MetasaveMaxTriggers: u32 = 64;
+	pub const MetasaveMaxTriggersPerKey: u32 = 16;
+	pub const MetasaveMaxGameTriggersPerKey: u32 = 2;
+	pub const MetasaveMaxClockPhases: u32 = 8;
+	pub const MetasaveMaxPhaseNameLen: u32 = 32;
+	pub MetasaveExpiryWeightBudget: Weight = Perbill::from_percent(10) * BlockWeights::get().max_block;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_metasave::Config for Runtime {
 	type Event = Event;
 	type GameID = u32;
+	type MaxKeyLen = MetasaveMaxKeyLen;
+	type MaxValueLen = MetasaveMaxValueLen;
+	type MaxExternalEntries = MetasaveMaxExternalEntries;
+	type MaxInternalEntries = MetasaveMaxInternalEntries;
+	type MaxNameLen = MetasaveMaxNameLen;
+	type MaxDescriptionLen = MetasaveMaxDescriptionLen;
+	type MaxWebsiteLen = MetasaveMaxWebsiteLen;
+	type MaxGenres = MetasaveMaxGenres;
+	type MaxGenreLen = MetasaveMaxGenreLen;
+	type MaxBatchOps = MetasaveMaxBatchOps;
+	type MaxEventValueLen = MetasaveMaxEventValueLen;
+	type MaxHistoryDepth = MetasaveMaxHistoryDepth;
+	type MaxHistoryRemovals = MetasaveMaxHistoryRemovals;
+	type MaxChangeLogLen = MetasaveMaxChangeLogLen;
+	type MaxExpiriesPerBlock = MetasaveMaxExpiriesPerBlock;
+	type MaxExpiries = MetasaveMaxExpiries;
+	type MaxSchedules = MetasaveMaxSchedules;
+	type MaxSchedulesPerBlock = MetasaveMaxSchedulesPerBlock;
+	type TimedScheduleSlot = MetasaveTimedScheduleSlot;
+	type MaxTimedSlotsPerBlock = MetasaveMaxTimedSlotsPerBlock;
+	type MaxScheduleDelay = MetasaveMaxScheduleDelay;
+	type MaxLeaderboards = MetasaveMaxLeaderboards;
+	type MaxLeaderboardSize = MetasaveMaxLeaderboardSize;
+	type MaxTriggers = MetasaveMaxTriggers;
+	type MaxTriggersPerKey = MetasaveMaxTriggersPerKey;
+	type MaxGameTriggersPerKey = MetasaveMaxGameTriggersPerKey;
+	type MaxClockPhases = MetasaveMaxClockPhases;
+	type MaxPhaseNameLen = MetasaveMaxPhaseNameLen;
+	type ExpiryWeightBudget = MetasaveExpiryWeightBudget;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -468,6 +529,75 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_metasave_runtime_api::MetasaveApi<Block, u32, AccountId, BlockNumber, u64> for Runtime {
+		fn world_entry(
+			game: u32,
+			route: pallet_metasave::Route,
+			key: Vec<u8>,
+		) -> Option<pallet_metasave::Entry<Vec<u8>, AccountId, BlockNumber, u64>> {
+			Metasave::world_entry_info(game, route, key)
+		}
+
+		fn user_entry(
+			game: u32,
+			user: AccountId,
+			route: pallet_metasave::Route,
+			key: Vec<u8>,
+		) -> Option<pallet_metasave::Entry<Vec<u8>, AccountId, BlockNumber, u64>> {
+			Metasave::user_entry_info(game, &user, route, key)
+		}
+
+		fn world_test_bit(game: u32, route: pallet_metasave::Route, key: Vec<u8>, bit: u32) -> Option<bool> {
+			Metasave::world_test_bit(game, route, key, bit)
+		}
+
+		fn user_test_bit(
+			game: u32,
+			user: AccountId,
+			route: pallet_metasave::Route,
+			key: Vec<u8>,
+			bit: u32,
+		) -> Option<bool> {
+			Metasave::user_test_bit(game, &user, route, key, bit)
+		}
+
+		fn world_history(
+			game: u32,
+			route: pallet_metasave::Route,
+			key: Vec<u8>,
+		) -> Vec<pallet_metasave::HistoryEntry<Vec<u8>, AccountId, BlockNumber>> {
+			Metasave::world_history(game, route, key)
+		}
+
+		fn user_history(
+			game: u32,
+			user: AccountId,
+			route: pallet_metasave::Route,
+			key: Vec<u8>,
+		) -> Vec<pallet_metasave::HistoryEntry<Vec<u8>, AccountId, BlockNumber>> {
+			Metasave::user_history(game, &user, route, key)
+		}
+
+		fn changes_since(
+			game: u32,
+			seq: u64,
+		) -> Vec<pallet_metasave::ChangeRecord<Vec<u8>, AccountId, BlockNumber>> {
+			Metasave::changes_since(game, seq)
+		}
+
+		fn world_time(game: u32) -> Option<pallet_metasave::clock::WorldTime<Vec<u8>>> {
+			Metasave::world_time(game)
+		}
+
+		fn leaderboard(
+			game: u32,
+			route: pallet_metasave::Route,
+			key: Vec<u8>,
+		) -> Option<Vec<(AccountId, pallet_metasave::numeric::Number)>> {
+			Metasave::leaderboard(game, route, key)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (